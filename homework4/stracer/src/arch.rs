@@ -0,0 +1,342 @@
+//! What differs between the architectures we trace on: which registers hold
+//! the system call number, arguments and return value, and how the calls are
+//! numbered. On x86_64 a tracee may also be a 32 bit program, whose calls
+//! follow the i386 ABI and numbering instead.
+
+use crate::system_call_args::*;
+use crate::system_call_names::*;
+use libc::{c_void, user_regs_struct};
+use nix::unistd::Pid;
+use std::collections::BTreeMap;
+use std::mem::MaybeUninit;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arch {
+    #[cfg(target_arch = "x86_64")]
+    X86_64,
+    /// A 32 bit program on an x86_64 kernel.
+    #[cfg(target_arch = "x86_64")]
+    I386,
+    #[cfg(target_arch = "aarch64")]
+    Aarch64,
+}
+
+impl Arch {
+    /// The architecture this tracer is built for, that of most tracees.
+    pub fn native() -> Arch {
+        #[cfg(target_arch = "x86_64")]
+        return Arch::X86_64;
+        #[cfg(target_arch = "aarch64")]
+        return Arch::Aarch64;
+    }
+
+    /// Every architecture a tracee of this tracer may be running.
+    pub fn all() -> &'static [Arch] {
+        #[cfg(target_arch = "x86_64")]
+        return &[Arch::X86_64, Arch::I386];
+        #[cfg(target_arch = "aarch64")]
+        return &[Arch::Aarch64];
+    }
+
+    /// The names of the calls by number. Numbers no call has are `""`.
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Arch::X86_64 => &SYSTEM_CALL_NAMES,
+            #[cfg(target_arch = "x86_64")]
+            Arch::I386 => &I386_SYSTEM_CALL_NAMES,
+            #[cfg(target_arch = "aarch64")]
+            Arch::Aarch64 => &AARCH64_SYSTEM_CALL_NAMES,
+        }
+    }
+
+    fn args(self) -> &'static [&'static [ArgKind]] {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Arch::X86_64 => &SYSTEM_CALL_ARGS,
+            #[cfg(target_arch = "x86_64")]
+            Arch::I386 => &I386_SYSTEM_CALL_ARGS,
+            #[cfg(target_arch = "aarch64")]
+            Arch::Aarch64 => &AARCH64_SYSTEM_CALL_ARGS,
+        }
+    }
+
+    /// The `AUDIT_ARCH_*` value seccomp filters see for calls made with
+    /// this architecture's calling convention.
+    pub fn audit_arch(self) -> u32 {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Arch::X86_64 => 0xc000_003e,
+            #[cfg(target_arch = "x86_64")]
+            Arch::I386 => 0x4000_0003,
+            #[cfg(target_arch = "aarch64")]
+            Arch::Aarch64 => 0xc000_00b7,
+        }
+    }
+
+    /// The number and name of every call the table knows.
+    pub fn syscalls(self) -> impl Iterator<Item = (u64, &'static str)> {
+        self.names().iter()
+            .enumerate()
+            .filter(|(_, name)| !name.is_empty())
+            .map(|(nr, &name)| (nr as u64, name))
+    }
+
+    /// The size of a pointer in the tracee.
+    pub fn word_size(self) -> usize {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Arch::I386 => 4,
+            _ => 8,
+        }
+    }
+
+    /// The name of call `nr`, or `syscall_0x1b4` style for numbers the table
+    /// has no call for, such as calls newer than it or the -1 of a skipped call.
+    pub fn syscall_name(self, nr: u64) -> &'static str {
+        match self.names().get(nr as usize) {
+            Some(name) if !name.is_empty() => name,
+            _ => unknown_name(nr),
+        }
+    }
+
+    /// The arguments call `nr` takes. We don't know for unknown calls, so
+    /// all six registers are shown.
+    pub fn syscall_args(self, nr: u64) -> &'static [ArgKind] {
+        match self.args().get(nr as usize) {
+            Some(args) if !self.names()[nr as usize].is_empty() => args,
+            _ => &[ArgKind::Long; 6],
+        }
+    }
+
+    /// The name of every call on any architecture a tracee may be running.
+    pub fn all_names() -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = Vec::new();
+        for arch in Arch::all() {
+            for (_, name) in arch.syscalls() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+}
+
+/// Names for the numbers of unknown calls, made once per number and then kept
+/// for the rest of the trace like those in the tables.
+fn unknown_name(nr: u64) -> &'static str {
+    static NAMES: Mutex<BTreeMap<u64, &'static str>> = Mutex::new(BTreeMap::new());
+    let mut names = NAMES.lock().unwrap();
+    names.entry(nr).or_insert_with(|| Box::leak(format!("syscall_{:#x}", nr).into_boxed_str()))
+}
+
+/// The registers of a tracee stopped at a system call, and the architecture
+/// whose calling convention they follow.
+pub struct Regs {
+    pub arch: Arch,
+    raw: user_regs_struct,
+    /// The call number was changed, which some architectures keep apart from
+    /// the general purpose registers.
+    nr_changed: bool,
+}
+
+/// The code segment selector of 32 bit programs on an x86_64 kernel.
+#[cfg(target_arch = "x86_64")]
+const IA32_CS: u64 = 0x23;
+
+#[cfg(target_arch = "x86_64")]
+impl Regs {
+    /// The registers of the stopped tracee `pid`.
+    pub fn get(pid: Pid) -> nix::Result<Regs> {
+        use nix::sys::ptrace::{self, Request};
+        let raw = unsafe {
+            let mut regs = MaybeUninit::<user_regs_struct>::uninit();
+            #[allow(deprecated)]
+            ptrace::ptrace(Request::PTRACE_GETREGS,
+                           pid,
+                           libc::PT_NULL as *mut c_void,
+                           regs.as_mut_ptr() as *mut c_void)?;
+            regs.assume_init()
+        };
+        let arch = if raw.cs == IA32_CS { Arch::I386 } else { Arch::X86_64 };
+        Ok(Regs { arch, raw, nr_changed: false })
+    }
+
+    /// Overwrite the registers of the stopped tracee `pid` with these.
+    pub fn set(&self, pid: Pid) -> nix::Result<()> {
+        use nix::sys::ptrace::{self, Request};
+        unsafe {
+            #[allow(deprecated)]
+            ptrace::ptrace(Request::PTRACE_SETREGS,
+                           pid,
+                           libc::PT_NULL as *mut c_void,
+                           &self.raw as *const _ as *mut c_void).map(|_| ())
+        }
+    }
+
+    pub fn nr(&self) -> u64 {
+        match self.arch {
+            Arch::I386 => self.raw.orig_rax as u32 as u64,
+            Arch::X86_64 => self.raw.orig_rax,
+        }
+    }
+
+    /// The six arguments, in the order the call takes them.
+    pub fn args(&self) -> [u64; 6] {
+        let r = &self.raw;
+        match self.arch {
+            Arch::I386 => {
+                let args = [r.rbx, r.rcx, r.rdx, r.rsi, r.rdi, r.rbp];
+                [0, 1, 2, 3, 4, 5].map(|i| args[i] as u32 as u64)
+            },
+            Arch::X86_64 => [r.rdi, r.rsi, r.rdx, r.r10, r.r8, r.r9],
+        }
+    }
+
+    pub fn retval(&self) -> i64 {
+        match self.arch {
+            Arch::I386 => self.raw.rax as i32 as i64,
+            Arch::X86_64 => self.raw.rax as i64,
+        }
+    }
+
+    pub fn set_retval(&mut self, retval: i64) {
+        self.raw.rax = retval as u64;
+    }
+
+    /// Have the kernel skip the call being entered, as if it were made with
+    /// number -1, which fails with `ENOSYS`.
+    pub fn skip_call(&mut self) {
+        self.raw.orig_rax = u64::MAX;
+        self.nr_changed = true;
+    }
+
+    /// The kernel sets `rax` to `-ENOSYS` before a syscall-enter stop, the
+    /// only way to tell entry and exit apart for a task we did not see enter.
+    pub fn is_entry(&self) -> bool {
+        self.retval() == -(libc::ENOSYS as i64)
+    }
+
+    /// Where the tracee is stopped.
+    pub fn pc(&self) -> u64 {
+        self.raw.rip
+    }
+
+    /// The general purpose registers and the return address by DWARF
+    /// number, as unwind tables refer to them.
+    pub fn dwarf_registers(&self) -> Vec<u64> {
+        let r = &self.raw;
+        vec![r.rax, r.rdx, r.rcx, r.rbx, r.rsi, r.rdi, r.rbp, r.rsp,
+             r.r8, r.r9, r.r10, r.r11, r.r12, r.r13, r.r14, r.r15, r.rip]
+    }
+}
+
+/// `PTRACE_GETREGSET` and `PTRACE_SETREGSET` with register set `set`.
+#[cfg(target_arch = "aarch64")]
+unsafe fn regset(request: libc::c_uint, pid: Pid, set: libc::c_int, data: *mut c_void,
+                 len: usize) -> nix::Result<()> {
+    let mut iov = libc::iovec { iov_base: data, iov_len: len };
+    let res = libc::ptrace(request, libc::pid_t::from(pid), set as usize as *mut c_void,
+                           &mut iov as *mut libc::iovec as *mut c_void);
+    nix::errno::Errno::result(res).map(|_| ())
+}
+
+/// The register set holding the call number, which on aarch64 is kept apart
+/// from `x8` once the call is entered.
+#[cfg(target_arch = "aarch64")]
+const NT_ARM_SYSTEM_CALL: libc::c_int = 0x404;
+
+#[cfg(target_arch = "aarch64")]
+impl Regs {
+    /// The registers of the stopped tracee `pid`.
+    pub fn get(pid: Pid) -> nix::Result<Regs> {
+        let raw = unsafe {
+            let mut regs = MaybeUninit::<user_regs_struct>::uninit();
+            regset(libc::PTRACE_GETREGSET, pid, libc::NT_PRSTATUS,
+                   regs.as_mut_ptr() as *mut c_void, std::mem::size_of::<user_regs_struct>())?;
+            regs.assume_init()
+        };
+        Ok(Regs { arch: Arch::Aarch64, raw, nr_changed: false })
+    }
+
+    /// Overwrite the registers of the stopped tracee `pid` with these.
+    pub fn set(&self, pid: Pid) -> nix::Result<()> {
+        unsafe {
+            let mut raw = self.raw;
+            regset(libc::PTRACE_SETREGSET, pid, libc::NT_PRSTATUS,
+                   &mut raw as *mut _ as *mut c_void, std::mem::size_of::<user_regs_struct>())?;
+            if self.nr_changed {
+                let mut nr = self.raw.regs[8] as libc::c_int;
+                regset(libc::PTRACE_SETREGSET, pid, NT_ARM_SYSTEM_CALL,
+                       &mut nr as *mut _ as *mut c_void, std::mem::size_of::<libc::c_int>())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn nr(&self) -> u64 {
+        self.raw.regs[8]
+    }
+
+    /// The six arguments, in the order the call takes them.
+    pub fn args(&self) -> [u64; 6] {
+        let r = &self.raw.regs;
+        [r[0], r[1], r[2], r[3], r[4], r[5]]
+    }
+
+    pub fn retval(&self) -> i64 {
+        self.raw.regs[0] as i64
+    }
+
+    pub fn set_retval(&mut self, retval: i64) {
+        self.raw.regs[0] = retval as u64;
+    }
+
+    /// Have the kernel skip the call being entered, as if it were made with
+    /// number -1, which fails with `ENOSYS`.
+    pub fn skip_call(&mut self) {
+        self.raw.regs[8] = u64::MAX;
+        self.nr_changed = true;
+    }
+
+    /// At syscall stops the kernel sets `x7` to 0 on entry and 1 on exit.
+    pub fn is_entry(&self) -> bool {
+        self.raw.regs[7] == 0
+    }
+
+    /// Where the tracee is stopped.
+    pub fn pc(&self) -> u64 {
+        self.raw.pc
+    }
+
+    /// The general purpose registers by DWARF number, as unwind tables
+    /// refer to them.
+    pub fn dwarf_registers(&self) -> Vec<u64> {
+        let mut registers = self.raw.regs.to_vec();
+        registers.push(self.raw.sp);
+        registers
+    }
+}
+
+#[test]
+fn tables_test() {
+    let arch = Arch::native();
+    assert!(!arch.syscall_name(0).is_empty());
+    assert!(Arch::all_names().contains(&"openat"));
+    #[cfg(target_arch = "x86_64")]
+    {
+        assert_eq!(Arch::X86_64.syscall_name(1), "write");
+        assert_eq!(Arch::I386.syscall_name(4), "write");
+        assert_eq!(Arch::I386.syscall_args(4), &[ArgKind::Fd, ArgKind::Ptr, ArgKind::Long]);
+        assert!(Arch::all_names().contains(&"mmap2"));
+        assert_eq!(Arch::X86_64.syscall_name(435), "clone3");
+        assert_eq!(Arch::X86_64.syscall_name(452), "fchmodat2");
+        assert_eq!(Arch::I386.syscall_name(466), "removexattrat");
+        assert_eq!(Arch::I386.syscall_name(453), "syscall_0x1c5");
+        assert_eq!(Arch::X86_64.syscall_name(335), "syscall_0x14f");
+        assert_eq!(Arch::X86_64.syscall_name(u64::MAX), "syscall_0xffffffffffffffff");
+        assert_eq!(Arch::X86_64.syscall_args(100000).len(), 6);
+    }
+}
@@ -1,5 +1,10 @@
 
 use structopt::StructOpt;
+use std::path::PathBuf;
+use stracer::command::{EnvChange, User};
+use stracer::inject::Injection;
+use stracer::output::Format;
+use stracer::timestamps::Timestamps;
 // use std::ffi::CString;
 
 #[derive(Debug, StructOpt)]
@@ -10,13 +15,108 @@ use structopt::StructOpt;
     rename_all = "snake"
 )]
 pub struct Opt {
-    #[structopt(short, long, conflicts_with = "to_trace", name = "dont_trace")]
+    #[structopt(short, long, conflicts_with_all = &["to_trace", "expr"], name = "dont_trace")]
     pub dont_trace: Option<Vec<String>>,
 
-    #[structopt(short, long, conflicts_with = "dont_trace", name = "to_trace")]
+    #[structopt(short, long, conflicts_with_all = &["dont_trace", "expr"], name = "to_trace")]
     pub to_trace: Option<Vec<String>>,
 
-    pub exe: String,
+    /// Filter expressions: `trace=SET` where SET is a comma separated list of
+    /// system call names, classes (%file, %desc, %network, %process, %memory,
+    /// %signal, %ipc) and /regexes, each optionally negated with `!`; and
+    /// `status=failed` or `status=successful`.
+    #[structopt(short = "e", long, number_of_values = 1)]
+    pub expr: Vec<String>,
+
+    /// Count calls, errors and time per system call and print a table at the
+    /// end instead of printing every call.
+    #[structopt(short = "c", long)]
+    pub summary: bool,
+
+    /// Write the trace to this file instead of standard output.
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+
+    /// Write the trace of each process to its own OUTPUT.PID file.
+    #[structopt(long, requires = "output")]
+    pub output_separately: bool,
+
+    /// Skip the selected calls and make them fail or return a value instead:
+    /// `SET:error=ERRNO[:when=WHEN]` or `SET:retval=VALUE[:when=WHEN]`, where
+    /// SET is as in `trace=` and WHEN is `N` (only the Nth call), `N+` (the
+    /// Nth call and all after it) or `N+S` (the Nth call and every Sth after).
+    #[structopt(long, number_of_values = 1)]
+    pub inject: Vec<Injection>,
+
+    /// Print calls as strace style text, or as one JSON object per line.
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    pub format: Format,
+
+    /// Prefix each line with the time of the call: wall clock time (clock),
+    /// wall clock time with microseconds (precise), seconds since the epoch
+    /// (unix) or seconds since the trace started (relative).
+    #[structopt(long, possible_values = &["clock", "precise", "unix", "relative"])]
+    pub timestamps: Option<Timestamps>,
+
+    /// Show the time spent in each call, as `<0.000123>` after its return value.
+    #[structopt(short = "T", long)]
+    pub syscall_times: bool,
+
+    /// Print at most this many bytes of the data read or written.
+    #[structopt(short, long, default_value = "32")]
+    pub string_limit: usize,
+
+    /// Also dump all data read from or written to these descriptors in hex.
+    #[structopt(long, use_delimiter = true)]
+    pub hex_dump: Vec<i32>,
+
+    /// Print the path, or the socket addresses, a file descriptor refers to
+    /// next to it, as in `read(3</etc/passwd>, ...)`.
+    #[structopt(short = "y", long)]
+    pub decode_fds: bool,
+
+    /// At the end, print the tree of processes traced: who started whom,
+    /// every program they executed with its arguments and environment, and
+    /// when and how they ended.
+    #[structopt(long)]
+    pub tree: bool,
+
+    /// Trace only the process started or attached to and its threads, not
+    /// the processes it forks.
+    #[structopt(long)]
+    pub no_follow_forks: bool,
+
+    /// Have a seccomp-BPF filter stop the traced program only for the calls
+    /// we need, making traces of a few calls much faster. Calls the filter
+    /// picks fail with ENOSYS if a traced process outlives the tracer.
+    #[structopt(long, conflicts_with_all = &["pid", "no_follow_forks"])]
+    pub seccomp_bpf: bool,
+
+    /// Print the call stack of the program under each traced call, unwound
+    /// as the call is entered.
+    #[structopt(short = "k", long)]
+    pub stack_trace: bool,
+
+    /// Run the command as this user, given by name or uid, with its groups.
+    #[structopt(short, long, conflicts_with = "pid")]
+    pub user: Option<User>,
+
+    /// Change the environment of the command: `VAR=VALUE` sets a variable,
+    /// `-VAR` removes one.
+    #[structopt(short = "E", long, number_of_values = 1, allow_hyphen_values = true,
+                conflicts_with = "pid")]
+    pub env: Vec<EnvChange>,
+
+    /// Run the command in this directory.
+    #[structopt(long, parse(from_os_str), conflicts_with = "pid")]
+    pub directory: Option<PathBuf>,
+
+    /// Attach to the running process with this pid instead of running a command.
+    #[structopt(short, long, conflicts_with = "exe")]
+    pub pid: Option<i32>,
+
+    #[structopt(required_unless = "pid")]
+    pub exe: Option<String>,
 
     #[structopt(raw(true))]
     pub exe_args: Vec<String>,
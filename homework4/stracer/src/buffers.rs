@@ -0,0 +1,154 @@
+//! The data passed to `read`, `write` and friends, printed as escaped strings
+//! in place of the buffer address, and as hex dumps for chosen descriptors.
+
+use crate::memory;
+use libc::iovec;
+use nix::unistd::Pid;
+use std::cmp::min;
+use std::mem;
+
+/// Where the data of a read/write style call is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataArg {
+    /// Index of the buffer argument, the descriptor is always the first.
+    pub buf: usize,
+    /// The buffer is an array of `iovec`s, counted by the next argument.
+    pub vectored: bool,
+    /// The data is only there once the call returns, as for `read`.
+    pub at_exit: bool,
+}
+
+pub fn data_arg(name: &str) -> Option<DataArg> {
+    let (vectored, at_exit) = match name {
+        "read" | "pread64" | "recvfrom" => (false, true),
+        "write" | "pwrite64" | "sendto" => (false, false),
+        "readv" | "preadv" | "preadv2" => (true, true),
+        "writev" | "pwritev" | "pwritev2" => (true, false),
+        _ => return None,
+    };
+    Some(DataArg { buf: 1, vectored, at_exit })
+}
+
+/// `bytes` as a quoted C string, followed by `...` if it was cut short.
+pub fn escape(bytes: &[u8], truncated: bool) -> String {
+    let mut s = String::with_capacity(bytes.len() + 2);
+    s.push('"');
+    for &b in bytes {
+        match b {
+            b'"' => s.push_str("\\\""),
+            b'\\' => s.push_str("\\\\"),
+            b'\n' => s.push_str("\\n"),
+            b'\r' => s.push_str("\\r"),
+            b'\t' => s.push_str("\\t"),
+            0x0b => s.push_str("\\v"),
+            0x0c => s.push_str("\\f"),
+            0x20..=0x7e => s.push(b as char),
+            _ => s.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    s.push('"');
+    if truncated {
+        s.push_str("...");
+    }
+    s
+}
+
+/// strace's `-e read=`/`-e write=` style dump, sixteen bytes per line, and
+/// a line of `...` if the data was `truncated`.
+pub fn hexdump(bytes: &[u8], truncated: bool) -> Vec<String> {
+    let mut lines = bytes.chunks(16).enumerate().map(|(i, chunk)| {
+        let hex = chunk.iter().map(|b| format!("{:02x}", b)).collect::<Vec<String>>();
+        let text = chunk.iter()
+            .map(|&b| if (0x20..0x7f).contains(&b) { b as char } else { '.' })
+            .collect::<String>();
+        format!(" | {:05x}  {:<48} {:<16} |", i * 16, hex.join(" "), text)
+    }).collect::<Vec<String>>();
+    if truncated {
+        lines.push(" | ...".to_string());
+    }
+    lines
+}
+
+/// The most bytes of a call we hex dump. The length is up to the tracee,
+/// which may well claim to have moved more than we want to hold.
+pub const MAX_DUMP: usize = 4 * 1024 * 1024;
+
+/// The data moved by a call, as printed and as read.
+pub struct Data {
+    pub text: String,
+    pub bytes: Vec<u8>,
+    /// Not all of the data is in `bytes`, there was more than `MAX_DUMP`.
+    pub truncated: bool,
+}
+
+/// Read the `len` bytes a call moved through argument `arg`. Only `limit`
+/// bytes are printed, and only those are read unless `full` asks for all of
+/// them, up to `MAX_DUMP`, for a hex dump.
+pub fn read_data(pid: Pid, args: &[u64], arg: DataArg, len: u64, limit: usize,
+                 full: bool) -> nix::Result<Data> {
+    let max = if full { MAX_DUMP } else { limit };
+    if !arg.vectored {
+        let bytes = memory::read_bytes(pid, args[arg.buf], min(len, max as u64) as usize)?;
+        let shown = min(bytes.len(), limit);
+        let text = escape(&bytes[..shown], (shown as u64) < len);
+        let truncated = full && (bytes.len() as u64) < len;
+        return Ok(Data { text, bytes, truncated });
+    }
+
+    // Vectored calls fill their buffers in order, `len` is the total moved.
+    let count = min(args[arg.buf + 1], IOV_MAX);
+    let mut remaining = len;
+    let mut parts = Vec::new();
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+    for i in 0..count {
+        let address = args[arg.buf].wrapping_add(i * mem::size_of::<iovec>() as u64);
+        let iov: iovec = memory::read_struct(pid, address)?;
+        let (base, iov_len) = (iov.iov_base as u64, iov.iov_len as u64);
+        let moved = min(iov_len, remaining);
+        remaining -= moved;
+        // The dump is capped for all buffers together.
+        let max = if full { MAX_DUMP - bytes.len() } else { limit };
+        let data = memory::read_bytes(pid, base, min(moved, max as u64) as usize)?;
+        let shown = min(data.len(), limit);
+        parts.push(format!("{{iov_base={}, iov_len={}}}",
+                           escape(&data[..shown], (shown as u64) < moved), iov_len));
+        truncated |= full && (data.len() as u64) < moved;
+        bytes.extend(data);
+    }
+    Ok(Data { text: format!("[{}]", parts.join(", ")), bytes, truncated })
+}
+
+/// The most `iovec`s a single call accepts.
+const IOV_MAX: u64 = 1024;
+
+#[test]
+fn escape_test() {
+    assert_eq!(escape(b"hi\n\"\\", false), "\"hi\\n\\\"\\\\\"");
+    assert_eq!(escape(&[0, 0xff, b'a'], true), "\"\\x00\\xffa\"...");
+}
+
+#[test]
+fn hexdump_test() {
+    let lines = hexdump(b"hello, world\n0123456789", false);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], " | 00000  68 65 6c 6c 6f 2c 20 77 6f 72 6c 64 0a 30 31 32  \
+                         hello, world.012 |");
+    assert_eq!(lines[1], " | 00010  33 34 35 36 37 38 39                             \
+                         3456789          |");
+    assert_eq!(hexdump(b"x", true)[1], " | ...");
+}
+
+#[test]
+fn read_data_test() {
+    // A length well past anything mapped, as a tracee may pass: only
+    // MAX_DUMP bytes are read.
+    let buf = vec![b'x'; MAX_DUMP + 16];
+    let pid = nix::unistd::getpid();
+    let arg = DataArg { buf: 1, vectored: false, at_exit: false };
+    let data = read_data(pid, &[1, buf.as_ptr() as u64], arg, 1 << 40, 4, true).unwrap();
+    assert_eq!((data.bytes.len(), data.truncated), (MAX_DUMP, true));
+    assert_eq!(data.text, "\"xxxx\"...");
+    let data = read_data(pid, &[1, buf.as_ptr() as u64], arg, 3, 4, true).unwrap();
+    assert_eq!((data.bytes, data.truncated), (b"xxx".to_vec(), false));
+}
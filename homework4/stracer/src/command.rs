@@ -0,0 +1,232 @@
+//! How the traced command is started: as which user, with which changes to
+//! our environment and in which directory. Applied in the child before it
+//! stops for us, so none of it shows up in the trace.
+
+use libc::{c_char, passwd};
+use nix::errno::Errno;
+use nix::unistd::{self, Gid, Uid};
+use std::env;
+use std::ffi::{CStr, CString, OsString};
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::ptr;
+use std::str::FromStr;
+
+/// A user to run the command as, with `-u NAME` or `-u UID`.
+#[derive(Debug, Clone)]
+pub struct User {
+    uid: Uid,
+    gid: Gid,
+    /// The name, to look up its supplementary groups. A uid without a
+    /// passwd entry has none.
+    name: Option<CString>,
+}
+
+/// The passwd entry of a user, looked up by `lookup` with the buffer it
+/// fills in.
+fn passwd_entry<F>(lookup: F) -> Option<User>
+    where F: Fn(*mut passwd, *mut libc::c_char, usize, *mut *mut passwd) -> libc::c_int {
+    let mut entry = MaybeUninit::<passwd>::uninit();
+    let mut buf = vec![0; 16384];
+    let mut result = ptr::null_mut();
+    let res = lookup(entry.as_mut_ptr(), buf.as_mut_ptr(), buf.len(), &mut result);
+    if res != 0 || result.is_null() {
+        return None;
+    }
+    unsafe {
+        let entry = entry.assume_init();
+        Some(User {
+            uid: Uid::from_raw(entry.pw_uid),
+            gid: Gid::from_raw(entry.pw_gid),
+            name: Some(CStr::from_ptr(entry.pw_name).to_owned()),
+        })
+    }
+}
+
+impl FromStr for User {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<User, String> {
+        if let Ok(uid) = s.parse::<libc::uid_t>() {
+            let user = passwd_entry(|entry, buf, len, result| unsafe {
+                libc::getpwuid_r(uid, entry, buf, len, result)
+            });
+            return Ok(user.unwrap_or(User { uid: Uid::from_raw(uid), gid: Gid::from_raw(uid), name: None }));
+        }
+        let name = CString::new(s).map_err(|_| format!("invalid user name {}", s))?;
+        passwd_entry(|entry, buf, len, result| unsafe {
+            libc::getpwnam_r(name.as_ptr(), entry, buf, len, result)
+        }).ok_or_else(|| format!("unknown user {}", s))
+    }
+}
+
+impl User {
+    /// The groups of this user, its own and the supplementary ones.
+    fn groups(&self) -> nix::Result<Vec<Gid>> {
+        match self.name {
+            Some(ref name) => unistd::getgrouplist(name, self.gid),
+            None => Ok(vec![self.gid]),
+        }
+    }
+}
+
+/// A change to the environment, `-E VAR=VALUE` or `-E -VAR`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvChange {
+    Set(String, String),
+    Unset(String),
+}
+
+impl FromStr for EnvChange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EnvChange, String> {
+        if let Some(var) = s.strip_prefix('-') {
+            if var.is_empty() || var.contains('=') {
+                return Err(format!("invalid variable to remove {}", var));
+            }
+            return Ok(EnvChange::Unset(var.to_string()));
+        }
+        match s.find('=') {
+            Some(idx) if idx > 0 => Ok(EnvChange::Set(s[..idx].to_string(), s[idx + 1..].to_string())),
+            _ => Err(format!("invalid environment change {}, expected VAR=VALUE or -VAR", s)),
+        }
+    }
+}
+
+/// Everything about starting the command besides the command itself.
+#[derive(Debug, Default)]
+pub struct Command {
+    pub user: Option<User>,
+    pub env: Vec<EnvChange>,
+    pub directory: Option<PathBuf>,
+}
+
+impl Command {
+    /// Everything the child needs to run `prog` with `args`, looked up and
+    /// allocated before we fork it.
+    pub fn prepare(&self, prog: &CString, args: &[CString]) -> nix::Result<Launch> {
+        let mut vars = env::vars_os().collect::<Vec<(OsString, OsString)>>();
+        for change in &self.env {
+            match change {
+                EnvChange::Set(var, value) => {
+                    vars.retain(|(v, _)| v != var.as_str());
+                    vars.push((var.into(), value.into()));
+                },
+                EnvChange::Unset(var) => vars.retain(|(v, _)| v != var.as_str()),
+            }
+        }
+        let env = vars.iter()
+            .map(|(var, value)| {
+                let mut entry = var.as_bytes().to_vec();
+                entry.push(b'=');
+                entry.extend_from_slice(value.as_bytes());
+                CString::new(entry).map_err(|_| nix::Error::invalid_argument())
+            })
+            .collect::<nix::Result<Vec<CString>>>()?;
+        let directory = match self.directory {
+            Some(ref directory) => Some(CString::new(directory.as_os_str().as_bytes())
+                                        .map_err(|_| nix::Error::invalid_argument())?),
+            None => None,
+        };
+        let user = match self.user {
+            Some(ref user) => Some((user.uid, user.gid, user.groups()?)),
+            None => None,
+        };
+        let args = args.to_vec();
+        let pointers = |strings: &[CString]| strings.iter()
+            .map(|s| s.as_ptr())
+            .chain(std::iter::once(ptr::null()))
+            .collect::<Vec<*const c_char>>();
+        Ok(Launch {
+            prog: prog.clone(),
+            argv: pointers(&args),
+            envp: pointers(&env),
+            _args: args,
+            _env: env,
+            directory,
+            user,
+        })
+    }
+}
+
+/// A command ready to be started in a forked child. The child of a threaded
+/// process may only make async-signal-safe calls, so that is all this does:
+/// no allocating, no reading the user database.
+pub struct Launch {
+    prog: CString,
+    /// Point into `_args` and `_env`, which are only kept for them.
+    argv: Vec<*const c_char>,
+    envp: Vec<*const c_char>,
+    _args: Vec<CString>,
+    _env: Vec<CString>,
+    directory: Option<CString>,
+    user: Option<(Uid, Gid, Vec<Gid>)>,
+}
+
+impl Launch {
+    /// Set up the calling process, the child about to execute the command.
+    /// Fails with what could not be set up.
+    pub fn apply(&self) -> Result<(), (&[u8], nix::Error)> {
+        if let Some(ref directory) = self.directory {
+            Errno::result(unsafe { libc::chdir(directory.as_ptr()) })
+                .map_err(|e| (directory.as_bytes(), e))?;
+        }
+        // Groups go first, once we changed our uid we may no longer change
+        // them.
+        if let Some((uid, gid, ref groups)) = self.user {
+            unistd::setgroups(groups)
+                .and_then(|_| unistd::setgid(gid))
+                .and_then(|_| unistd::setuid(uid))
+                .map_err(|e| (&b"cannot switch user"[..], e))?;
+        }
+        Ok(())
+    }
+
+    /// Execute the command, looked up in `PATH`. Only returns if that failed.
+    pub fn exec(&self) -> nix::Error {
+        unsafe { libc::execvpe(self.prog.as_ptr(), self.argv.as_ptr(), self.envp.as_ptr()) };
+        nix::Error::Sys(Errno::last())
+    }
+
+    /// The command, as we were asked to run it.
+    pub fn prog(&self) -> &CStr {
+        &self.prog
+    }
+}
+
+#[test]
+fn env_change_test() {
+    assert_eq!("A=b=c".parse(), Ok(EnvChange::Set("A".to_string(), "b=c".to_string())));
+    assert_eq!("A=".parse(), Ok(EnvChange::Set("A".to_string(), String::new())));
+    assert_eq!("-HOME".parse(), Ok(EnvChange::Unset("HOME".to_string())));
+    assert!("HOME".parse::<EnvChange>().is_err());
+    assert!("-".parse::<EnvChange>().is_err());
+}
+
+#[test]
+fn user_test() {
+    let root = "root".parse::<User>().unwrap();
+    assert_eq!((root.uid, root.gid), (Uid::from_raw(0), Gid::from_raw(0)));
+    assert_eq!("0".parse::<User>().unwrap().name, Some(CString::new("root").unwrap()));
+    assert_eq!("4242".parse::<User>().map(|user| user.name).ok(), Some(None));
+    assert!("no such user".parse::<User>().is_err());
+}
+
+#[test]
+fn prepare_test() {
+    let command = Command {
+        user: None,
+        env: vec!["-HOME".parse().unwrap(), "A=1".parse().unwrap(), "A=2".parse().unwrap()],
+        directory: Some(PathBuf::from("/tmp")),
+    };
+    let launch = command.prepare(&CString::new("true").unwrap(), &[CString::new("true").unwrap()]).unwrap();
+    assert!(!launch._env.iter().any(|entry| entry.as_bytes().starts_with(b"HOME=")));
+    let a = launch._env.iter().filter(|entry| entry.as_bytes().starts_with(b"A=")).collect::<Vec<_>>();
+    assert_eq!(a, vec![&CString::new("A=2").unwrap()]);
+    // Both pointer arrays end with NULL.
+    assert_eq!((launch.argv.len(), launch.envp.len()), (2, launch._env.len() + 1));
+    assert!(launch.envp.last().unwrap().is_null());
+    assert_eq!(launch.directory, Some(CString::new("/tmp").unwrap()));
+}
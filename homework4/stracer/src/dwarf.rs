@@ -0,0 +1,434 @@
+//! The DWARF call frame information in `.eh_frame`, which says for every
+//! instruction of a function where its caller's registers and return
+//! address are saved. Rules given as DWARF expressions are not supported;
+//! frames needing them are unwound by their frame pointer instead.
+
+use std::collections::HashMap;
+
+/// Registers we keep track of while unwinding, by DWARF number.
+pub const REGISTERS: usize = 33;
+
+/// Where the value a register had in the caller is, relative to the
+/// canonical frame address, the stack pointer just before the call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    Undefined,
+    SameValue,
+    /// Saved at CFA plus this.
+    Offset(i64),
+    /// Is CFA plus this.
+    ValOffset(i64),
+    /// Is in this other register.
+    Register(u16),
+}
+
+/// The rules in effect at an instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    /// CFA is this register plus an offset.
+    pub cfa: (u16, i64),
+    pub registers: HashMap<u16, Rule>,
+    /// The register holding the return address.
+    pub return_address: u16,
+}
+
+/// Reads the bytes of `.eh_frame` at `pos` on.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// The address of `.eh_frame`, for addresses relative to where they are.
+    section_addr: u64,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let b = self.bytes(8)?;
+        Some(u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let (mut value, mut shift) = (0u64, 0);
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let (mut value, mut shift) = (0i64, 0);
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Some(value);
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let len = self.data.get(self.pos..)?.iter().position(|&b| b == 0)?;
+        let string = self.bytes(len)?;
+        self.pos += 1;
+        Some(string)
+    }
+
+    /// A pointer in `DW_EH_PE_*` encoding `encoding`.
+    fn pointer(&mut self, encoding: u8) -> Option<u64> {
+        let at = self.section_addr.wrapping_add(self.pos as u64);
+        let value = match encoding & 0x0f {
+            0x00 => self.u64()?,
+            0x01 => self.uleb()?,
+            0x02 => u64::from(self.u16()?),
+            0x03 => u64::from(self.u32()?),
+            0x04 => self.u64()?,
+            0x09 => self.sleb()? as u64,
+            0x0a => self.u16()? as i16 as u64,
+            0x0b => self.u32()? as i32 as u64,
+            0x0c => self.u64()?,
+            _ => return None,
+        };
+        match encoding & 0x70 {
+            0x00 => Some(value),
+            0x10 => Some(at.wrapping_add(value)),
+            // Relative to sections we don't have.
+            _ => None,
+        }
+    }
+}
+
+/// A common information entry, shared by the FDEs of a binary.
+struct Cie {
+    code_align: u64,
+    data_align: i64,
+    return_address: u16,
+    pointer_encoding: u8,
+    augmented: bool,
+    /// The instructions setting up the rules at the start of each function.
+    instructions: (usize, usize),
+}
+
+/// The call frame information of a binary.
+pub struct EhFrame {
+    addr: u64,
+    data: Vec<u8>,
+    /// Per function, sorted by address: its start and end address, and
+    /// where its FDE is.
+    fdes: Vec<(u64, u64, usize)>,
+}
+
+impl EhFrame {
+    /// Index `data`, the contents of `.eh_frame` loaded at `addr`.
+    pub fn new(addr: u64, data: Vec<u8>) -> EhFrame {
+        let mut eh_frame = EhFrame { addr, data, fdes: Vec::new() };
+        let mut fdes = Vec::new();
+        let mut pos = 0;
+        while let Some((next, fde)) = eh_frame.entry(pos) {
+            if let Some((start, end)) = fde {
+                fdes.push((start, end, pos));
+            }
+            pos = next;
+        }
+        fdes.sort_unstable();
+        eh_frame.fdes = fdes;
+        eh_frame
+    }
+
+    fn reader(&self, pos: usize) -> Reader<'_> {
+        Reader { data: &self.data, pos, section_addr: self.addr }
+    }
+
+    /// The entry at `pos`: where the next one is, and if it is an FDE, the
+    /// addresses it covers.
+    fn entry(&self, pos: usize) -> Option<(usize, Option<(u64, u64)>)> {
+        let mut r = self.reader(pos);
+        let (len, end) = self.entry_len(&mut r)?;
+        if len == 0 {
+            return None;
+        }
+        let id_at = r.pos;
+        let id = r.u32()?;
+        if id == 0 {
+            return Some((end, None));
+        }
+        let cie = self.cie(id_at.checked_sub(id as usize)?)?;
+        let start = r.pointer(cie.pointer_encoding)?;
+        let range = r.pointer(cie.pointer_encoding & 0x0f)?;
+        Some((end, Some((start, start.wrapping_add(range)))))
+    }
+
+    fn entry_len(&self, r: &mut Reader) -> Option<(u64, usize)> {
+        let len = match r.u32()? {
+            0xffff_ffff => r.u64()?,
+            len => u64::from(len),
+        };
+        Some((len, r.pos.checked_add(len as usize)?))
+    }
+
+    fn cie(&self, pos: usize) -> Option<Cie> {
+        let mut r = self.reader(pos);
+        let (_, end) = self.entry_len(&mut r)?;
+        if r.u32()? != 0 {
+            return None;
+        }
+        let version = r.u8()?;
+        let augmentation = r.string()?;
+        if augmentation.windows(2).any(|w| w == b"eh") {
+            r.u64()?;
+        }
+        let code_align = r.uleb()?;
+        let data_align = r.sleb()?;
+        let return_address = if version == 1 { u64::from(r.u8()?) } else { r.uleb()? } as u16;
+        let mut pointer_encoding = 0;
+        let augmented = augmentation.first() == Some(&b'z');
+        if augmented {
+            let len = r.uleb()? as usize;
+            let data_end = r.pos.checked_add(len)?;
+            for &c in &augmentation[1..] {
+                match c {
+                    b'R' => pointer_encoding = r.u8()?,
+                    b'L' => { r.u8()?; },
+                    b'P' => {
+                        let encoding = r.u8()?;
+                        r.pointer(encoding & 0x7f)?;
+                    },
+                    _ => (),
+                }
+            }
+            r.pos = data_end;
+        }
+        Some(Cie {
+            code_align,
+            data_align,
+            return_address,
+            pointer_encoding,
+            augmented,
+            instructions: (r.pos, end),
+        })
+    }
+
+    /// The rules at `pc`, if the binary has them and we understand them.
+    pub fn rules(&self, pc: u64) -> Option<Rules> {
+        let idx = match self.fdes.binary_search_by_key(&pc, |&(start, _, _)| start) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        let (start, end, pos) = self.fdes[idx];
+        if pc >= end {
+            return None;
+        }
+        let mut r = self.reader(pos);
+        let (_, fde_end) = self.entry_len(&mut r)?;
+        let id_at = r.pos;
+        let cie = self.cie(id_at.checked_sub(r.u32()? as usize)?)?;
+        r.pointer(cie.pointer_encoding)?;
+        r.pointer(cie.pointer_encoding & 0x0f)?;
+        if cie.augmented {
+            let len = r.uleb()? as usize;
+            r.pos = r.pos.checked_add(len)?;
+        }
+        let mut rules = Rules { cfa: (0, 0), registers: HashMap::new(), return_address: cie.return_address };
+        let initial = self.execute(&cie, cie.instructions, start, u64::MAX, &mut rules, None)?;
+        self.execute(&cie, (r.pos, fde_end), start, pc, &mut rules, Some(&initial))?;
+        Some(rules)
+    }
+
+    /// Run the instructions in `range` for the function at `start` until
+    /// they get past `pc`. `initial` are the rules the CIE set up, which
+    /// `DW_CFA_restore` goes back to. Returns the rules.
+    fn execute(&self, cie: &Cie, range: (usize, usize), start: u64, pc: u64, rules: &mut Rules,
+               initial: Option<&Rules>) -> Option<Rules> {
+        let mut r = self.reader(range.0);
+        let mut loc = start;
+        let mut stack = Vec::new();
+        let restore = |rules: &mut Rules, reg: u16| {
+            match initial.and_then(|initial| initial.registers.get(&reg)) {
+                Some(&rule) => rules.registers.insert(reg, rule),
+                None => rules.registers.remove(&reg),
+            };
+        };
+        while r.pos < range.1 {
+            let op = r.u8()?;
+            let advance = match op >> 6 {
+                1 => Some(u64::from(op & 0x3f)),
+                2 => {
+                    let offset = (r.uleb()? as i64).wrapping_mul(cie.data_align);
+                    rules.registers.insert(u16::from(op & 0x3f), Rule::Offset(offset));
+                    None
+                },
+                3 => {
+                    restore(rules, u16::from(op & 0x3f));
+                    None
+                },
+                _ => match op {
+                    0x00 => None,
+                    0x01 => {
+                        loc = r.pointer(cie.pointer_encoding)?;
+                        if loc > pc {
+                            break;
+                        }
+                        None
+                    },
+                    0x02 => Some(u64::from(r.u8()?)),
+                    0x03 => Some(u64::from(r.u16()?)),
+                    0x04 => Some(u64::from(r.u32()?)),
+                    0x05 => {
+                        let reg = r.uleb()? as u16;
+                        let offset = (r.uleb()? as i64).wrapping_mul(cie.data_align);
+                        rules.registers.insert(reg, Rule::Offset(offset));
+                        None
+                    },
+                    0x06 => {
+                        restore(rules, r.uleb()? as u16);
+                        None
+                    },
+                    0x07 => {
+                        rules.registers.insert(r.uleb()? as u16, Rule::Undefined);
+                        None
+                    },
+                    0x08 => {
+                        rules.registers.insert(r.uleb()? as u16, Rule::SameValue);
+                        None
+                    },
+                    0x09 => {
+                        let reg = r.uleb()? as u16;
+                        rules.registers.insert(reg, Rule::Register(r.uleb()? as u16));
+                        None
+                    },
+                    0x0a => {
+                        stack.push((rules.cfa, rules.registers.clone()));
+                        None
+                    },
+                    0x0b => {
+                        let (cfa, registers) = stack.pop()?;
+                        rules.cfa = cfa;
+                        rules.registers = registers;
+                        None
+                    },
+                    0x0c => {
+                        rules.cfa = (r.uleb()? as u16, r.uleb()? as i64);
+                        None
+                    },
+                    0x0d => {
+                        rules.cfa.0 = r.uleb()? as u16;
+                        None
+                    },
+                    0x0e => {
+                        rules.cfa.1 = r.uleb()? as i64;
+                        None
+                    },
+                    0x11 => {
+                        let reg = r.uleb()? as u16;
+                        let offset = r.sleb()?.wrapping_mul(cie.data_align);
+                        rules.registers.insert(reg, Rule::Offset(offset));
+                        None
+                    },
+                    0x12 => {
+                        rules.cfa = (r.uleb()? as u16, r.sleb()?.wrapping_mul(cie.data_align));
+                        None
+                    },
+                    0x13 => {
+                        rules.cfa.1 = r.sleb()?.wrapping_mul(cie.data_align);
+                        None
+                    },
+                    0x14 => {
+                        let reg = r.uleb()? as u16;
+                        let offset = (r.uleb()? as i64).wrapping_mul(cie.data_align);
+                        rules.registers.insert(reg, Rule::ValOffset(offset));
+                        None
+                    },
+                    0x15 => {
+                        let reg = r.uleb()? as u16;
+                        let offset = r.sleb()?.wrapping_mul(cie.data_align);
+                        rules.registers.insert(reg, Rule::ValOffset(offset));
+                        None
+                    },
+                    // DW_CFA_GNU_args_size, only of interest to exceptions.
+                    0x2e => {
+                        r.uleb()?;
+                        None
+                    },
+                    // DW_CFA_AARCH64_negate_ra_state, pointer authentication.
+                    0x2d => None,
+                    // DW_CFA_def_cfa_expression, DW_CFA_expression,
+                    // DW_CFA_val_expression and anything unknown.
+                    _ => return None,
+                },
+            };
+            if let Some(delta) = advance {
+                loc = loc.wrapping_add(delta.wrapping_mul(cie.code_align));
+                if loc > pc {
+                    break;
+                }
+            }
+        }
+        Some(rules.clone())
+    }
+}
+
+#[test]
+fn rules_test() {
+    // A CIE as x86_64 compilers emit it, then an FDE for 0x1000..0x1010
+    // pushing rbp at 0x1001 and moving the CFA to rbp at 0x1004.
+    let mut data = vec![
+        0x14, 0, 0, 0, 0, 0, 0, 0, 1, b'z', b'R', 0, 1, 0x78, 0x10, 1, 0x1b,
+        0x0c, 7, 8, 0x90, 1, 0, 0,
+    ];
+    let fde_at = data.len();
+    data.extend_from_slice(&[0x1c, 0, 0, 0]);
+    data.extend_from_slice(&(fde_at as u32 + 4).to_le_bytes());
+    // pc_begin is relative to where it is, 0x1000 - (fde_at + 8).
+    data.extend_from_slice(&((0x1000 - (fde_at as i32 + 8)) as u32).to_le_bytes());
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.extend_from_slice(&[0, 0x41, 0x0e, 16, 0x86, 2, 0x43, 0x0d, 6, 0, 0, 0, 0]);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    let eh_frame = EhFrame::new(0, data);
+    let at = |pc| eh_frame.rules(pc).map(|rules| (rules.cfa, rules.registers.get(&6).cloned()));
+    assert_eq!(eh_frame.rules(0x1000).unwrap().registers.get(&16), Some(&Rule::Offset(-8)));
+    assert_eq!(at(0x1000), Some(((7, 8), None)));
+    assert_eq!(at(0x1002), Some(((7, 16), Some(Rule::Offset(-16)))));
+    assert_eq!(at(0x1008), Some(((6, 16), Some(Rule::Offset(-16)))));
+    assert_eq!(at(0x1010), None);
+    // Malformed tables, with any byte changed, are read without panicking.
+    let data = eh_frame.data.clone();
+    for i in 0..data.len() {
+        for &byte in &[0x00, 0x7f, 0x80, 0xff] {
+            let mut data = data.clone();
+            data[i] = byte;
+            let eh_frame = EhFrame::new(0, data);
+            for pc in 0xff8..0x1018 {
+                eh_frame.rules(pc);
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Just enough ELF reading for stack traces: where a binary's segments are
+//! loaded, the names of its functions and its `.eh_frame` unwind tables.
+//! Only 64 bit little endian files, those of native tracees, are read.
+
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+const PT_LOAD: u32 = 1;
+const STT_FUNC: u8 = 2;
+/// The sizes of the 64 bit program and section headers, which the fields
+/// we read must fit in.
+const PHENT_SIZE: u64 = 56;
+const SHENT_SIZE: u64 = 64;
+
+fn invalid(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, what.to_string())
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[at..at + 4]);
+    u32::from_le_bytes(word)
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    let mut word = [0; 8];
+    word.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(word)
+}
+
+struct Section {
+    name: u32,
+    kind: u32,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+}
+
+/// A loadable segment: where it goes in memory and where it is in the file.
+struct Load {
+    vaddr: u64,
+    offset: u64,
+    size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Symbol {
+    start: u64,
+    size: u64,
+    name: String,
+}
+
+pub struct Elf {
+    loads: Vec<Load>,
+    /// Function symbols sorted by address.
+    symbols: Vec<Symbol>,
+    /// The address of `.eh_frame` and its contents.
+    pub eh_frame: Option<(u64, Vec<u8>)>,
+}
+
+fn read_at(file: &File, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+    let size = file.metadata()?.len();
+    if offset.checked_add(len).is_none_or(|end| end > size) {
+        return Err(invalid("data past the end of the file"));
+    }
+    let mut bytes = vec![0; len as usize];
+    file.read_exact_at(&mut bytes, offset)?;
+    Ok(bytes)
+}
+
+impl Elf {
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Elf> {
+        let file = File::open(path)?;
+        let header = read_at(&file, 0, 64)?;
+        if &header[..4] != b"\x7fELF" || header[4] != 2 || header[5] != 1 {
+            return Err(invalid("not a 64 bit little endian ELF file"));
+        }
+        let (phoff, shoff) = (u64_at(&header, 32), u64_at(&header, 40));
+        let (phentsize, phnum) = (u16_at(&header, 54) as u64, u16_at(&header, 56) as u64);
+        let (shentsize, shnum) = (u16_at(&header, 58) as u64, u16_at(&header, 60) as u64);
+        let shstrndx = u16_at(&header, 62) as usize;
+        if phentsize < PHENT_SIZE {
+            return Err(invalid("program headers too small"));
+        }
+        // Stripped to the bone, some binaries have no section headers.
+        if shnum > 0 && shentsize < SHENT_SIZE {
+            return Err(invalid("section headers too small"));
+        }
+
+        let program_headers = read_at(&file, phoff, phentsize * phnum)?;
+        let loads = program_headers.chunks_exact(phentsize as usize)
+            .filter(|ph| u32_at(ph, 0) == PT_LOAD)
+            .map(|ph| Load { offset: u64_at(ph, 8), vaddr: u64_at(ph, 16), size: u64_at(ph, 32) })
+            .collect();
+
+        let section_headers = if shnum > 0 { read_at(&file, shoff, shentsize * shnum)? } else { Vec::new() };
+        let sections = section_headers.chunks_exact(shentsize.max(1) as usize)
+            .map(|sh| Section {
+                name: u32_at(sh, 0),
+                kind: u32_at(sh, 4),
+                addr: u64_at(sh, 16),
+                offset: u64_at(sh, 24),
+                size: u64_at(sh, 32),
+                link: u32_at(sh, 40),
+            })
+            .collect::<Vec<Section>>();
+        let names = match sections.get(shstrndx) {
+            Some(names) => read_at(&file, names.offset, names.size)?,
+            None => Vec::new(),
+        };
+        let section_name = |section: &Section| {
+            let name = names.get(section.name as usize..).unwrap_or(&[]);
+            name.split(|&b| b == 0).next().unwrap_or(&[]).to_vec()
+        };
+
+        let mut symbols = Vec::new();
+        for section in sections.iter().filter(|s| s.kind == SHT_SYMTAB || s.kind == SHT_DYNSYM) {
+            let strings = match sections.get(section.link as usize) {
+                Some(strings) => read_at(&file, strings.offset, strings.size)?,
+                None => continue,
+            };
+            let table = read_at(&file, section.offset, section.size)?;
+            for sym in table.chunks_exact(24) {
+                let start = u64_at(sym, 8);
+                if sym[4] & 0xf != STT_FUNC || start == 0 {
+                    continue;
+                }
+                let name = strings.get(u32_at(sym, 0) as usize..).unwrap_or(&[]);
+                let name = name.split(|&b| b == 0).next().unwrap_or(&[]);
+                symbols.push(Symbol {
+                    start,
+                    size: u64_at(sym, 16),
+                    name: String::from_utf8_lossy(name).into_owned(),
+                });
+            }
+        }
+        symbols.sort_by_key(|s| s.start);
+        symbols.dedup_by_key(|s| s.start);
+
+        let eh_frame = match sections.iter().find(|s| section_name(s) == b".eh_frame") {
+            Some(section) => Some((section.addr, read_at(&file, section.offset, section.size)?)),
+            None => None,
+        };
+        Ok(Elf { loads, symbols, eh_frame })
+    }
+
+    /// The address `offset` in the file is loaded at, before relocation.
+    pub fn vaddr(&self, offset: u64) -> Option<u64> {
+        self.loads.iter()
+            .find(|load| offset >= load.offset && offset - load.offset < load.size)
+            .map(|load| (offset - load.offset).wrapping_add(load.vaddr))
+    }
+
+    /// The function `vaddr` is in, and how far into it.
+    pub fn symbol(&self, vaddr: u64) -> Option<(&str, u64)> {
+        let idx = match self.symbols.binary_search_by_key(&vaddr, |s| s.start) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        let symbol = &self.symbols[idx];
+        // Hand written assembly often has no size.
+        if symbol.size == 0 || vaddr - symbol.start < symbol.size {
+            Some((&symbol.name, vaddr - symbol.start))
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn read_self_test() {
+    let elf = Elf::read("/proc/self/exe").unwrap();
+    assert!(elf.eh_frame.is_some());
+    assert!(elf.vaddr(0).is_some());
+    let symbol = elf.symbols.iter()
+        .find(|s| s.size > 4 && s.name.contains("3elf14read_self_test"))
+        .unwrap();
+    assert_eq!(elf.symbol(symbol.start + 4), Some((symbol.name.as_str(), 4)));
+}
+
+#[test]
+fn read_malformed_test() {
+    use std::fs;
+    let path = std::env::temp_dir().join(format!("stracer-elf-{}", nix::unistd::getpid()));
+    let mut header = b"\x7fELF\x02\x01\x01".to_vec();
+    header.resize(64, 0);
+    // No program headers of any size, nor sections.
+    fs::write(&path, &header).unwrap();
+    assert!(Elf::read(&path).is_err());
+    // Program headers claiming to be 56 bytes long, beyond the end of the file.
+    header[54] = 56;
+    header[56] = 1;
+    header[32] = 64;
+    fs::write(&path, &header).unwrap();
+    assert!(Elf::read(&path).is_err());
+    // One program header and no sections, as after sstrip.
+    header.resize(64 + 56, 0);
+    fs::write(&path, &header).unwrap();
+    assert!(Elf::read(&path).unwrap().symbols.is_empty());
+    // Section headers too small for their fields.
+    header[58] = 8;
+    header[60] = 1;
+    fs::write(&path, &header).unwrap();
+    assert!(Elf::read(&path).is_err());
+    fs::remove_file(&path).unwrap();
+}
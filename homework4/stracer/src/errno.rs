@@ -0,0 +1,212 @@
+//! Names and messages of the Linux errno values, used to print failed system
+//! calls as `-1 ENOENT (No such file or directory)`.
+
+/// Errno values exported to user space, indexed by the errno value. The gaps
+/// at 41 and 58 were never assigned.
+pub static ERRNO_NAMES: [Option<(&str, &str)>; 134] = [
+    None,
+    Some(("EPERM", "Operation not permitted")),
+    Some(("ENOENT", "No such file or directory")),
+    Some(("ESRCH", "No such process")),
+    Some(("EINTR", "Interrupted system call")),
+    Some(("EIO", "Input/output error")),
+    Some(("ENXIO", "No such device or address")),
+    Some(("E2BIG", "Argument list too long")),
+    Some(("ENOEXEC", "Exec format error")),
+    Some(("EBADF", "Bad file descriptor")),
+    Some(("ECHILD", "No child processes")),
+    Some(("EAGAIN", "Resource temporarily unavailable")),
+    Some(("ENOMEM", "Cannot allocate memory")),
+    Some(("EACCES", "Permission denied")),
+    Some(("EFAULT", "Bad address")),
+    Some(("ENOTBLK", "Block device required")),
+    Some(("EBUSY", "Device or resource busy")),
+    Some(("EEXIST", "File exists")),
+    Some(("EXDEV", "Invalid cross-device link")),
+    Some(("ENODEV", "No such device")),
+    Some(("ENOTDIR", "Not a directory")),
+    Some(("EISDIR", "Is a directory")),
+    Some(("EINVAL", "Invalid argument")),
+    Some(("ENFILE", "Too many open files in system")),
+    Some(("EMFILE", "Too many open files")),
+    Some(("ENOTTY", "Inappropriate ioctl for device")),
+    Some(("ETXTBSY", "Text file busy")),
+    Some(("EFBIG", "File too large")),
+    Some(("ENOSPC", "No space left on device")),
+    Some(("ESPIPE", "Illegal seek")),
+    Some(("EROFS", "Read-only file system")),
+    Some(("EMLINK", "Too many links")),
+    Some(("EPIPE", "Broken pipe")),
+    Some(("EDOM", "Numerical argument out of domain")),
+    Some(("ERANGE", "Numerical result out of range")),
+    Some(("EDEADLK", "Resource deadlock avoided")),
+    Some(("ENAMETOOLONG", "File name too long")),
+    Some(("ENOLCK", "No locks available")),
+    Some(("ENOSYS", "Function not implemented")),
+    Some(("ENOTEMPTY", "Directory not empty")),
+    Some(("ELOOP", "Too many levels of symbolic links")),
+    None,
+    Some(("ENOMSG", "No message of desired type")),
+    Some(("EIDRM", "Identifier removed")),
+    Some(("ECHRNG", "Channel number out of range")),
+    Some(("EL2NSYNC", "Level 2 not synchronized")),
+    Some(("EL3HLT", "Level 3 halted")),
+    Some(("EL3RST", "Level 3 reset")),
+    Some(("ELNRNG", "Link number out of range")),
+    Some(("EUNATCH", "Protocol driver not attached")),
+    Some(("ENOCSI", "No CSI structure available")),
+    Some(("EL2HLT", "Level 2 halted")),
+    Some(("EBADE", "Invalid exchange")),
+    Some(("EBADR", "Invalid request descriptor")),
+    Some(("EXFULL", "Exchange full")),
+    Some(("ENOANO", "No anode")),
+    Some(("EBADRQC", "Invalid request code")),
+    Some(("EBADSLT", "Invalid slot")),
+    None,
+    Some(("EBFONT", "Bad font file format")),
+    Some(("ENOSTR", "Device not a stream")),
+    Some(("ENODATA", "No data available")),
+    Some(("ETIME", "Timer expired")),
+    Some(("ENOSR", "Out of streams resources")),
+    Some(("ENONET", "Machine is not on the network")),
+    Some(("ENOPKG", "Package not installed")),
+    Some(("EREMOTE", "Object is remote")),
+    Some(("ENOLINK", "Link has been severed")),
+    Some(("EADV", "Advertise error")),
+    Some(("ESRMNT", "Srmount error")),
+    Some(("ECOMM", "Communication error on send")),
+    Some(("EPROTO", "Protocol error")),
+    Some(("EMULTIHOP", "Multihop attempted")),
+    Some(("EDOTDOT", "RFS specific error")),
+    Some(("EBADMSG", "Bad message")),
+    Some(("EOVERFLOW", "Value too large for defined data type")),
+    Some(("ENOTUNIQ", "Name not unique on network")),
+    Some(("EBADFD", "File descriptor in bad state")),
+    Some(("EREMCHG", "Remote address changed")),
+    Some(("ELIBACC", "Can not access a needed shared library")),
+    Some(("ELIBBAD", "Accessing a corrupted shared library")),
+    Some(("ELIBSCN", ".lib section in a.out corrupted")),
+    Some(("ELIBMAX", "Attempting to link in too many shared libraries")),
+    Some(("ELIBEXEC", "Cannot exec a shared library directly")),
+    Some(("EILSEQ", "Invalid or incomplete multibyte or wide character")),
+    Some(("ERESTART", "Interrupted system call should be restarted")),
+    Some(("ESTRPIPE", "Streams pipe error")),
+    Some(("EUSERS", "Too many users")),
+    Some(("ENOTSOCK", "Socket operation on non-socket")),
+    Some(("EDESTADDRREQ", "Destination address required")),
+    Some(("EMSGSIZE", "Message too long")),
+    Some(("EPROTOTYPE", "Protocol wrong type for socket")),
+    Some(("ENOPROTOOPT", "Protocol not available")),
+    Some(("EPROTONOSUPPORT", "Protocol not supported")),
+    Some(("ESOCKTNOSUPPORT", "Socket type not supported")),
+    Some(("EOPNOTSUPP", "Operation not supported")),
+    Some(("EPFNOSUPPORT", "Protocol family not supported")),
+    Some(("EAFNOSUPPORT", "Address family not supported by protocol")),
+    Some(("EADDRINUSE", "Address already in use")),
+    Some(("EADDRNOTAVAIL", "Cannot assign requested address")),
+    Some(("ENETDOWN", "Network is down")),
+    Some(("ENETUNREACH", "Network is unreachable")),
+    Some(("ENETRESET", "Network dropped connection on reset")),
+    Some(("ECONNABORTED", "Software caused connection abort")),
+    Some(("ECONNRESET", "Connection reset by peer")),
+    Some(("ENOBUFS", "No buffer space available")),
+    Some(("EISCONN", "Transport endpoint is already connected")),
+    Some(("ENOTCONN", "Transport endpoint is not connected")),
+    Some(("ESHUTDOWN", "Cannot send after transport endpoint shutdown")),
+    Some(("ETOOMANYREFS", "Too many references: cannot splice")),
+    Some(("ETIMEDOUT", "Connection timed out")),
+    Some(("ECONNREFUSED", "Connection refused")),
+    Some(("EHOSTDOWN", "Host is down")),
+    Some(("EHOSTUNREACH", "No route to host")),
+    Some(("EALREADY", "Operation already in progress")),
+    Some(("EINPROGRESS", "Operation now in progress")),
+    Some(("ESTALE", "Stale file handle")),
+    Some(("EUCLEAN", "Structure needs cleaning")),
+    Some(("ENOTNAM", "Not a XENIX named type file")),
+    Some(("ENAVAIL", "No XENIX semaphores available")),
+    Some(("EISNAM", "Is a named type file")),
+    Some(("EREMOTEIO", "Remote I/O error")),
+    Some(("EDQUOT", "Disk quota exceeded")),
+    Some(("ENOMEDIUM", "No medium found")),
+    Some(("EMEDIUMTYPE", "Wrong medium type")),
+    Some(("ECANCELED", "Operation canceled")),
+    Some(("ENOKEY", "Required key not available")),
+    Some(("EKEYEXPIRED", "Key has expired")),
+    Some(("EKEYREVOKED", "Key has been revoked")),
+    Some(("EKEYREJECTED", "Key was rejected by service")),
+    Some(("EOWNERDEAD", "Owner died")),
+    Some(("ENOTRECOVERABLE", "State not recoverable")),
+    Some(("ERFKILL", "Operation not possible due to RF-kill")),
+    Some(("EHWPOISON", "Memory page has hardware error")),
+];
+
+/// Errno values internal to the kernel which user space should never see, but
+/// which show up at syscall exit stops of interrupted calls.
+pub static KERNEL_ERRNO_NAMES: [(i64, &str, &str); 18] = [
+    (512, "ERESTARTSYS", "To be restarted if SA_RESTART is set"),
+    (513, "ERESTARTNOINTR", "To be restarted"),
+    (514, "ERESTARTNOHAND", "To be restarted if no handler"),
+    (515, "ENOIOCTLCMD", "No ioctl command"),
+    (516, "ERESTART_RESTARTBLOCK", "Interrupted by signal"),
+    (517, "EPROBE_DEFER", "Driver requests probe retry"),
+    (518, "EOPENSTALE", "Open found a stale dentry"),
+    (519, "ENOPARAM", "Parameter not supported"),
+    (521, "EBADHANDLE", "Illegal NFS file handle"),
+    (522, "ENOTSYNC", "Update synchronization mismatch"),
+    (523, "EBADCOOKIE", "Cookie is stale"),
+    (524, "ENOTSUPP", "Operation is not supported"),
+    (525, "ETOOSMALL", "Buffer or request is too small"),
+    (526, "ESERVERFAULT", "An untranslatable error occurred"),
+    (527, "EBADTYPE", "Type not supported by server"),
+    (528, "EJUKEBOX", "Request initiated, but will not complete before timeout"),
+    (529, "EIOCBQUEUED", "iocb queued, will get completion event"),
+    (530, "ERECALLCONFLICT", "Conflict with recalled state"),
+];
+
+/// The largest errno a system call can return, anything between `-MAX_ERRNO`
+/// and `-1` is an error rather than a value.
+pub const MAX_ERRNO: i64 = 4095;
+
+/// Name and message of `errno`, if it is known.
+pub fn lookup(errno: i64) -> Option<(&'static str, &'static str)> {
+    if errno > 0 && (errno as usize) < ERRNO_NAMES.len() {
+        return ERRNO_NAMES[errno as usize];
+    }
+    KERNEL_ERRNO_NAMES.iter()
+        .find(|&&(e, _, _)| e == errno)
+        .map(|&(_, name, message)| (name, message))
+}
+
+/// The errno called `name`, such as `ENOENT`.
+pub fn from_name(name: &str) -> Option<i64> {
+    ERRNO_NAMES.iter()
+        .position(|e| e.map(|(n, _)| n) == Some(name))
+        .map(|errno| errno as i64)
+        .or_else(|| KERNEL_ERRNO_NAMES.iter()
+                 .find(|&&(_, n, _)| n == name)
+                 .map(|&(errno, _, _)| errno))
+}
+
+/// The errno carried by a raw system call return value, if it is an error.
+pub fn from_retval(retval: i64) -> Option<i64> {
+    if (-MAX_ERRNO..0).contains(&retval) { Some(-retval) } else { None }
+}
+
+/// Render `errno` the way strace does, e.g. `ENOENT (No such file or directory)`.
+pub fn describe(errno: i64) -> String {
+    match lookup(errno) {
+        Some((name, message)) => format!("{} ({})", name, message),
+        None => format!("{}", errno),
+    }
+}
+
+#[test]
+fn describe_test() {
+    assert_eq!(describe(2), "ENOENT (No such file or directory)");
+    assert_eq!(describe(11), "EAGAIN (Resource temporarily unavailable)");
+    assert_eq!(describe(512), "ERESTARTSYS (To be restarted if SA_RESTART is set)");
+    assert_eq!(describe(41), "41");
+    assert_eq!(from_retval(-2), Some(2));
+    assert_eq!(from_retval(0x7f0000000000), None);
+    assert_eq!(from_name("ENOSPC"), Some(28));
+}
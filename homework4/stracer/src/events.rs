@@ -0,0 +1,172 @@
+use crate::arch::Arch;
+use crate::errno;
+use crate::json;
+use crate::stack::Frame;
+use crate::system_call_args::{self, RetKind};
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Everything a trace reports, in the order it happened.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Syscall(SyscallEvent),
+    Process(ProcessEvent),
+    Signal(SignalEvent),
+}
+
+/// A traced task starting, executing a program or ending, rather than a call
+/// it made.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessEvent {
+    /// `parent` started `pid`, a new thread of its own or a new process.
+    Spawned { pid: Pid, parent: Pid, thread: bool },
+    /// Process `pid` now runs a new program, started with `argv`.
+    Exec { pid: Pid, argv: Vec<String> },
+    /// Task `pid` ended, a whole process if it is the process id.
+    Exited { pid: Pid, code: i32 },
+    Killed { pid: Pid, signal: Signal, core_dumped: bool },
+}
+
+/// A signal on its way to a traced thread.
+#[derive(Debug, Clone)]
+pub struct SignalEvent {
+    pub pid: Pid,
+    pub tid: Pid,
+    pub signal: Signal,
+    /// `si_code`, who sent it or why.
+    pub code: i32,
+    /// The fields of its `siginfo_t`, as strace prints them.
+    pub info: String,
+}
+
+/// One system call made by a traced thread, filled in at its entry stop and
+/// completed at its exit stop.
+#[derive(Debug, Clone)]
+pub struct SyscallEvent {
+    /// The process, that is the thread group, making the call.
+    pub pid: Pid,
+    /// The thread making the call.
+    pub tid: Pid,
+    /// The architecture whose numbering `nr` is in.
+    pub arch: Arch,
+    pub nr: u64,
+    pub name: &'static str,
+    /// The arguments as they are printed, one per argument the call takes.
+    pub args: Vec<String>,
+    pub raw_args: Vec<u64>,
+    /// `None` until the call returns, and for calls such as `exit_group`
+    /// which never do.
+    pub retval: Option<i64>,
+    pub entered_at: SystemTime,
+    pub duration: Option<Duration>,
+    /// The call was skipped and `retval` made up by fault injection.
+    pub injected: bool,
+    /// The data moved by a read or write, kept to be hex dumped. At most
+    /// 4 MiB of it, `data_truncated` if there was more.
+    pub data: Option<Vec<u8>>,
+    pub data_truncated: bool,
+    /// The text line was started at entry, with this many arguments.
+    pub started: Option<usize>,
+    /// The call stack at entry, innermost frame first, with `-k`.
+    pub stack: Option<Vec<Frame>>,
+}
+
+impl SyscallEvent {
+    /// The error the call failed with.
+    pub fn errno(&self) -> Option<i64> {
+        self.retval.and_then(errno::from_retval)
+    }
+
+    /// The return value the way strace prints it.
+    pub fn format_retval(&self) -> String {
+        match self.retval {
+            None => "?".to_string(),
+            Some(retval) => if let Some(err) = errno::from_retval(retval) {
+                format!("-1 {}", errno::describe(err))
+            } else if system_call_args::ret_kind(self.name) == RetKind::Address {
+                // Addresses of 32 bit tracees come sign extended.
+                let mask = u64::MAX >> (64 - 8 * self.arch.word_size());
+                format!("{:#x}", retval as u64 & mask)
+            } else {
+                format!("{}", retval)
+            },
+        }
+    }
+
+    /// `name(args) = retval`.
+    pub fn to_text(&self) -> String {
+        format!("{}{}", self.entry_text(self.args.len()), self.exit_text(self.args.len()))
+    }
+
+    /// The start of the text line, up to and including the first `shown`
+    /// arguments, which can be printed as the call is entered.
+    pub fn entry_text(&self, shown: usize) -> String {
+        let more = if shown > 0 && shown < self.args.len() { ", " } else { "" };
+        format!("{}({}{}", self.name, self.args[..shown].join(", "), more)
+    }
+
+    /// The rest of the text line after `entry_text(shown)`.
+    pub fn exit_text(&self, shown: usize) -> String {
+        let injected = if self.injected { " (INJECTED)" } else { "" };
+        format!("{}) = {}{}", self.args[shown..].join(", "), self.format_retval(), injected)
+    }
+
+    pub fn to_json(&self) -> String {
+        let errno = self.errno();
+        let retval = if errno.is_some() { Some(-1) } else { self.retval };
+        json::object(&[
+            ("pid", self.pid.to_string()),
+            ("tid", self.tid.to_string()),
+            ("syscall", json::string(self.name)),
+            ("nr", self.nr.to_string()),
+            ("args", json::array(&self.args, |a| json::string(a))),
+            ("raw_args", json::array(&self.raw_args, |a| a.to_string())),
+            ("retval", json::optional(&retval, |r| r.to_string())),
+            ("raw_retval", json::optional(&self.retval, |r| r.to_string())),
+            ("errno", json::optional(&errno, |e| {
+                json::string(errno::lookup(*e).map(|(name, _)| name).unwrap_or("UNKNOWN"))
+            })),
+            ("entry_time", seconds(self.entered_at.duration_since(UNIX_EPOCH).unwrap_or_default())),
+            ("exit_time", json::optional(&self.duration, |d| {
+                seconds((self.entered_at + *d).duration_since(UNIX_EPOCH).unwrap_or_default())
+            })),
+            ("duration", json::optional(&self.duration, |d| seconds(*d))),
+            ("injected", self.injected.to_string()),
+            ("stack", json::optional(&self.stack, |frames| json::array(frames, Frame::to_json))),
+        ])
+    }
+}
+
+fn seconds(d: Duration) -> String {
+    format!("{}.{:06}", d.as_secs(), d.subsec_micros())
+}
+
+#[test]
+fn format_retval_test() {
+    let mut call = SyscallEvent {
+        pid: Pid::from_raw(1),
+        tid: Pid::from_raw(1),
+        arch: Arch::native(),
+        nr: 0,
+        name: "read",
+        args: Vec::new(),
+        raw_args: Vec::new(),
+        retval: Some(30042),
+        entered_at: UNIX_EPOCH,
+        duration: None,
+        injected: false,
+        data: None,
+        data_truncated: false,
+        started: None,
+        stack: None,
+    };
+    assert_eq!(call.format_retval(), "30042");
+    call.retval = None;
+    assert_eq!(call.format_retval(), "?");
+    call.retval = Some(-2);
+    assert_eq!(call.format_retval(), "-1 ENOENT (No such file or directory)");
+    call.name = "mmap";
+    call.retval = Some(0x7f00_0000_0000);
+    assert_eq!(call.format_retval(), "0x7f0000000000");
+}
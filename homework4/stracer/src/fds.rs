@@ -0,0 +1,194 @@
+//! What the file descriptors of each traced process refer to, so that with
+//! `-y` `read(3</etc/passwd>, ...)` is printed instead of `read(3, ...)`.
+//!
+//! The table is kept up to date from the calls which open, duplicate and close
+//! descriptors, and anything it does not know is looked up in `/proc/PID/fd`.
+
+use crate::events::SyscallEvent;
+use crate::memory;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Calls returning a new descriptor which we look up in `/proc`.
+const OPENING_CALLS: &[&str] = &[
+    "open", "openat", "openat2", "creat", "socket", "accept", "accept4", "epoll_create",
+    "epoll_create1", "eventfd", "eventfd2", "memfd_create", "signalfd", "signalfd4",
+    "timerfd_create", "inotify_init", "inotify_init1", "open_by_handle_at", "perf_event_open",
+    "fanotify_init", "userfaultfd", "pidfd_open",
+];
+
+/// Other calls changing what descriptors refer to.
+const CHANGING_CALLS: &[&str] = &[
+    "dup", "dup2", "dup3", "fcntl", "pipe", "pipe2", "socketpair", "close", "close_range",
+    "execve", "execveat",
+];
+
+/// Whether the table needs to see calls to `name` to stay up to date.
+pub fn tracks(name: &str) -> bool {
+    OPENING_CALLS.contains(&name) || CHANGING_CALLS.contains(&name)
+}
+
+pub struct FdTable {
+    /// Per thread group, the target of each descriptor as `/proc/PID/fd`
+    /// shows it: a path, or `socket:[INODE]`, `pipe:[INODE]` and the like.
+    tables: HashMap<Pid, HashMap<i32, String>>,
+}
+
+impl FdTable {
+    pub fn new() -> FdTable {
+        FdTable { tables: HashMap::new() }
+    }
+
+    /// A forked child starts with a copy of its parent's descriptors. Threads
+    /// share the table of their thread group.
+    pub fn inherit(&mut self, parent: Pid, child: Pid) {
+        if parent != child {
+            let table = self.tables.get(&parent).cloned().unwrap_or_default();
+            self.tables.insert(child, table);
+        }
+    }
+
+    pub fn forget(&mut self, pid: Pid) {
+        self.tables.remove(&pid);
+    }
+
+    /// What `fd` of process `pid` refers to, if it is open.
+    pub fn lookup(&mut self, pid: Pid, fd: i32) -> Option<String> {
+        let target = match self.tables.entry(pid).or_default().entry(fd) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(proc_fd(pid, fd)?),
+        };
+        Some(match socket_inode(target) {
+            // Sockets are looked up each time, their addresses change.
+            Some(inode) => describe_socket(pid, inode).unwrap_or_else(|| target.clone()),
+            None => target.clone(),
+        })
+    }
+
+    /// Bring the table of `call.pid` up to date with a finished call.
+    pub fn update(&mut self, call: &SyscallEvent) {
+        let retval = match call.retval {
+            Some(retval) if call.errno().is_none() => retval,
+            _ => return,
+        };
+        let args = &call.raw_args;
+        let pid = call.pid;
+        let table = self.tables.entry(pid).or_default();
+        match call.name {
+            name if OPENING_CALLS.contains(&name) => {
+                table.remove(&(retval as i32));
+                if let Some(target) = proc_fd(pid, retval as i32) {
+                    table.insert(retval as i32, target);
+                }
+            },
+            "dup" | "dup2" | "dup3" => {
+                let target = table.get(&(args[0] as i32)).cloned();
+                match target.or_else(|| proc_fd(pid, retval as i32)) {
+                    Some(target) => table.insert(retval as i32, target),
+                    None => table.remove(&(retval as i32)),
+                };
+            },
+            "fcntl" if args[1] == libc::F_DUPFD as u64 || args[1] == libc::F_DUPFD_CLOEXEC as u64 => {
+                table.remove(&(retval as i32));
+                if let Some(target) = table.get(&(args[0] as i32)).cloned() {
+                    table.insert(retval as i32, target);
+                }
+            },
+            "pipe" | "pipe2" | "socketpair" => {
+                let at = if call.name == "socketpair" { args[3] } else { args[0] };
+                if let Ok(pair) = memory::read_struct::<[i32; 2]>(call.tid, at) {
+                    for &fd in &pair {
+                        table.remove(&fd);
+                        if let Some(target) = proc_fd(pid, fd) {
+                            table.insert(fd, target);
+                        }
+                    }
+                }
+            },
+            "close" => {
+                table.remove(&(args[0] as i32));
+            },
+            "close_range" => {
+                table.retain(|&fd, _| (fd as u64) < args[0] || fd as u64 > args[1]);
+            },
+            // Descriptors may have been closed on exec, look them up again.
+            "execve" | "execveat" => table.clear(),
+            _ => (),
+        }
+    }
+}
+
+fn proc_fd(pid: Pid, fd: i32) -> Option<String> {
+    fs::read_link(format!("/proc/{}/fd/{}", pid, fd)).ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+fn socket_inode(target: &str) -> Option<u64> {
+    target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// The protocol and addresses of the socket with `inode`, from the tables in
+/// `/proc/PID/net`.
+fn describe_socket(pid: Pid, inode: u64) -> Option<String> {
+    for &(file, protocol) in &[("tcp", "TCP"), ("tcp6", "TCPv6"), ("udp", "UDP"), ("udp6", "UDPv6")] {
+        let table = match fs::read_to_string(format!("/proc/{}/net/{}", pid, file)) {
+            Ok(table) => table,
+            Err(_) => continue,
+        };
+        for line in table.lines().skip(1) {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() > 9 && fields[9].parse() == Ok(inode) {
+                let local = inet_address(fields[1])?;
+                return Some(match inet_address(fields[2]) {
+                    Some(ref remote) if !remote.ends_with(":0") => {
+                        format!("{}:[{}->{}]", protocol, local, remote)
+                    },
+                    _ => format!("{}:[{}]", protocol, local),
+                });
+            }
+        }
+    }
+    let table = fs::read_to_string(format!("/proc/{}/net/unix", pid)).ok()?;
+    for line in table.lines().skip(1) {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() > 6 && fields[6].parse() == Ok(inode) {
+            return Some(match fields.get(7) {
+                Some(path) => format!("UNIX:[{},{}]", inode, path),
+                None => format!("UNIX:[{}]", inode),
+            });
+        }
+    }
+    None
+}
+
+/// An `ADDRESS:PORT` pair as `/proc/net/tcp` has it, the address in hex as
+/// 32 bit words in host order.
+pub fn inet_address(field: &str) -> Option<String> {
+    let (address, port) = field.split_at(field.find(':')?);
+    let port = u16::from_str_radix(&port[1..], 16).ok()?;
+    let mut bytes = Vec::new();
+    for i in (0..address.len()).step_by(8) {
+        let word = u32::from_str_radix(address.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    match bytes.len() {
+        4 => Some(format!("{}:{}", Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]), port)),
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&bytes);
+            Some(format!("[{}]:{}", Ipv6Addr::from(octets), port))
+        },
+        _ => None,
+    }
+}
+
+#[test]
+fn inet_address_test() {
+    assert_eq!(inet_address("0100007F:1538"), Some("127.0.0.1:5432".to_string()));
+    assert_eq!(inet_address("00000000000000000000000001000000:0050"),
+               Some("[::1]:80".to_string()));
+    assert_eq!(inet_address("nonsense"), None);
+}
@@ -0,0 +1,202 @@
+//! strace style `-e` expressions selecting which calls are printed, such as
+//! `-e trace=%file,%network,!mmap`, `-e trace=/^mmap|munmap$` or
+//! `-e status=failed`.
+
+use crate::arch::Arch;
+use regex::Regex;
+use std::str::FromStr;
+use super::TraceOption;
+
+/// Which calls to print depending on how they returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    All,
+    Successful,
+    Failed,
+}
+
+impl Status {
+    pub fn matches(self, failed: bool) -> bool {
+        match self {
+            Status::All => true,
+            Status::Successful => !failed,
+            Status::Failed => failed,
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Status, String> {
+        match s {
+            "all" => Ok(Status::All),
+            "successful" => Ok(Status::Successful),
+            "failed" => Ok(Status::Failed),
+            _ => Err(format!("invalid status {}", s)),
+        }
+    }
+}
+
+const FILE: &[&str] = &[
+    "open", "stat", "lstat", "access", "execve", "truncate", "chdir", "rename", "mkdir",
+    "rmdir", "creat", "link", "unlink", "symlink", "readlink", "chmod", "chown", "lchown",
+    "utime", "mknod", "uselib", "statfs", "pivot_root", "chroot", "acct", "mount", "umount2",
+    "swapon", "swapoff", "quotactl", "setxattr", "lsetxattr", "getxattr", "lgetxattr",
+    "listxattr", "llistxattr", "removexattr", "lremovexattr", "utimes", "inotify_add_watch",
+    "openat", "mkdirat", "mknodat", "fchownat", "futimesat", "newfstatat", "unlinkat",
+    "renameat", "linkat", "symlinkat", "readlinkat", "fchmodat", "faccessat", "utimensat",
+    "fanotify_mark", "name_to_handle_at", "renameat2", "execveat", "statx", "open_tree",
+    "move_mount", "fsopen", "fspick", "openat2", "faccessat2", "mount_setattr", "fchmodat2",
+    "setxattrat", "getxattrat", "listxattrat", "removexattrat",
+];
+
+const DESC: &[&str] = &[
+    "read", "write", "open", "close", "fstat", "poll", "lseek", "mmap", "ioctl", "pread64",
+    "pwrite64", "readv", "writev", "pipe", "select", "dup", "dup2", "sendfile", "fcntl",
+    "flock", "fsync", "fdatasync", "ftruncate", "getdents", "fchdir", "creat", "fchmod",
+    "fchown", "fstatfs", "readahead", "fsetxattr", "fgetxattr", "flistxattr", "fremovexattr",
+    "epoll_create", "getdents64", "fadvise64", "epoll_wait", "epoll_ctl", "mq_open",
+    "mq_timedsend", "mq_timedreceive", "mq_notify", "mq_getsetattr", "inotify_init",
+    "inotify_add_watch", "inotify_rm_watch", "openat", "mkdirat", "mknodat", "fchownat",
+    "futimesat", "newfstatat", "unlinkat", "renameat", "linkat", "symlinkat", "readlinkat",
+    "fchmodat", "faccessat", "pselect6", "ppoll", "splice", "tee", "sync_file_range",
+    "vmsplice", "utimensat", "epoll_pwait", "signalfd", "timerfd_create", "eventfd",
+    "fallocate", "timerfd_settime", "timerfd_gettime", "signalfd4", "eventfd2",
+    "epoll_create1", "dup3", "pipe2", "inotify_init1", "preadv", "pwritev",
+    "perf_event_open", "fanotify_init", "fanotify_mark", "name_to_handle_at",
+    "open_by_handle_at", "syncfs", "setns", "finit_module", "renameat2", "memfd_create",
+    "bpf", "execveat", "userfaultfd", "copy_file_range", "preadv2", "pwritev2", "statx",
+    "io_uring_setup", "io_uring_enter", "io_uring_register", "open_tree", "move_mount",
+    "fsopen", "fsconfig", "fsmount", "fspick", "pidfd_open", "close_range", "openat2",
+    "pidfd_getfd", "faccessat2", "epoll_pwait2", "mount_setattr", "memfd_secret",
+    "cachestat", "fchmodat2", "setxattrat", "getxattrat", "listxattrat", "removexattrat",
+];
+
+const NETWORK: &[&str] = &[
+    "sendfile", "socket", "connect", "accept", "sendto", "recvfrom", "sendmsg", "recvmsg",
+    "shutdown", "bind", "listen", "getsockname", "getpeername", "socketpair", "setsockopt",
+    "getsockopt", "accept4", "recvmmsg", "sendmmsg",
+];
+
+const PROCESS: &[&str] = &[
+    "clone", "fork", "vfork", "execve", "exit", "wait4", "kill", "tkill", "exit_group",
+    "tgkill", "waitid", "rt_sigqueueinfo", "unshare", "rt_tgsigqueueinfo", "execveat",
+    "pidfd_send_signal", "clone3",
+];
+
+const MEMORY: &[&str] = &[
+    "mmap", "mprotect", "munmap", "brk", "mremap", "msync", "mincore", "madvise", "shmat",
+    "shmdt", "mlock", "munlock", "mlockall", "munlockall", "remap_file_pages", "mbind",
+    "set_mempolicy", "get_mempolicy", "migrate_pages", "move_pages", "mlock2",
+    "pkey_mprotect", "process_madvise", "memfd_secret", "process_mrelease",
+    "set_mempolicy_home_node", "map_shadow_stack", "mseal",
+];
+
+const SIGNAL: &[&str] = &[
+    "rt_sigaction", "rt_sigprocmask", "rt_sigreturn", "pause", "kill", "rt_sigpending",
+    "rt_sigtimedwait", "rt_sigqueueinfo", "rt_sigsuspend", "sigaltstack", "tkill", "tgkill",
+    "signalfd", "signalfd4", "rt_tgsigqueueinfo", "pidfd_send_signal",
+];
+
+const IPC: &[&str] = &[
+    "shmget", "shmat", "shmctl", "semget", "semop", "semctl", "shmdt", "msgget", "msgsnd",
+    "msgrcv", "msgctl", "semtimedop",
+];
+
+fn class(name: &str) -> Result<&'static [&'static str], String> {
+    match name {
+        "file" => Ok(FILE),
+        "desc" => Ok(DESC),
+        "network" | "net" => Ok(NETWORK),
+        "process" => Ok(PROCESS),
+        "memory" => Ok(MEMORY),
+        "signal" => Ok(SIGNAL),
+        "ipc" => Ok(IPC),
+        _ => Err(format!("invalid system call class %{}", name)),
+    }
+}
+
+/// The system calls one element of a `trace=` list stands for.
+fn resolve(item: &str) -> Result<Vec<&'static str>, String> {
+    if item == "all" {
+        Ok(Arch::all_names())
+    } else if item == "none" {
+        Ok(Vec::new())
+    } else if let Some(name) = item.strip_prefix('%') {
+        class(name).map(|names| names.to_vec())
+    } else if let Some(pattern) = item.strip_prefix('/') {
+        let re = Regex::new(pattern).map_err(|e| e.to_string())?;
+        Ok(Arch::all_names().into_iter().filter(|name| re.is_match(name)).collect())
+    } else {
+        Arch::all_names().into_iter()
+            .find(|&name| name == item)
+            .map(|name| vec![name])
+            .ok_or_else(|| format!("invalid system call {}", item))
+    }
+}
+
+/// The system calls selected by a comma separated `trace=` list. Elements are
+/// applied in order, those starting with `!` remove calls from the selection,
+/// which starts out with every call if the first element is negated.
+pub fn parse_set(set: &str) -> Result<Vec<String>, String> {
+    let mut selected: Vec<&'static str> = Vec::new();
+    for (i, item) in set.split(',').enumerate() {
+        let (negated, item) = match item.strip_prefix('!') {
+            Some(item) => (true, item),
+            None => (false, item),
+        };
+        let names = resolve(item)?;
+        if negated {
+            if i == 0 {
+                selected = Arch::all_names();
+            }
+            selected.retain(|name| !names.contains(name));
+        } else {
+            for name in names {
+                if !selected.contains(&name) {
+                    selected.push(name);
+                }
+            }
+        }
+    }
+    Ok(selected.into_iter().map(String::from).collect())
+}
+
+/// Apply one `-e` expression, `trace=SET`, `status=STATUS` or a bare `SET`.
+pub fn apply(expr: &str, topt: &mut TraceOption, status: &mut Status) -> Result<(), String> {
+    let (qualifier, value) = match expr.find('=') {
+        Some(idx) => (&expr[..idx], &expr[idx + 1..]),
+        None => ("trace", expr),
+    };
+    match qualifier {
+        "trace" | "t" => *topt = TraceOption::Trace(parse_set(value)?),
+        "status" => *status = value.parse()?,
+        _ => return Err(format!("invalid qualifier {}", qualifier)),
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_set_test() {
+    assert_eq!(parse_set("%network,!connect,bind").unwrap().len(), NETWORK.len() - 1);
+    assert_eq!(parse_set("/^mmap$|munmap$").unwrap(), vec!["mmap", "munmap"]);
+    let all_but_mmap = parse_set("!mmap").unwrap();
+    assert_eq!(all_but_mmap.len(), Arch::all_names().len() - 1);
+    assert!(!all_but_mmap.contains(&"mmap".to_string()));
+    assert!(parse_set("%nope").is_err());
+    assert!(parse_set("opne").is_err());
+}
+
+#[test]
+fn apply_test() {
+    let mut topt = TraceOption::Ignore(Vec::new());
+    let mut status = Status::All;
+    apply("status=failed", &mut topt, &mut status).unwrap();
+    apply("openat,close", &mut topt, &mut status).unwrap();
+    assert_eq!(status, Status::Failed);
+    match topt {
+        TraceOption::Trace(v) => assert_eq!(v, vec!["openat", "close"]),
+        _ => panic!("expected a trace list"),
+    }
+}
@@ -0,0 +1,456 @@
+//! Symbolic names for the bitmasks and constants passed to system calls, so
+//! `openat(AT_FDCWD, "/etc/passwd", O_RDONLY|O_CLOEXEC)` is printed instead of
+//! `openat(-100, "/etc/passwd", 0x80000)`. The values are those of x86_64
+//! and i386, and of aarch64 where it numbers them differently.
+
+use crate::arch::Arch;
+
+type Names = &'static [(u64, &'static str)];
+
+const AT_FDCWD: i32 = -100;
+
+const OPEN_ACCMODE: u64 = 0o3;
+const O_CREAT: u64 = 0o100;
+const OPEN_ACCESS: Names = &[(0o0, "O_RDONLY"), (0o1, "O_WRONLY"), (0o2, "O_RDWR")];
+const OPEN_FLAGS: Names = &[
+    (0o4010000, "O_SYNC"), (0o20200000, "O_TMPFILE"),
+    (0o100, "O_CREAT"), (0o200, "O_EXCL"), (0o400, "O_NOCTTY"), (0o1000, "O_TRUNC"), (0o2000, "O_APPEND"), (0o4000, "O_NONBLOCK"),
+    (0o10000, "O_DSYNC"), (0o20000, "O_ASYNC"), (0o40000, "O_DIRECT"),
+    (0o100000, "O_LARGEFILE"), (0o200000, "O_DIRECTORY"), (0o400000, "O_NOFOLLOW"),
+    (0o1000000, "O_NOATIME"), (0o2000000, "O_CLOEXEC"), (0o10000000, "O_PATH"),
+];
+/// aarch64 has `O_DIRECTORY`, `O_NOFOLLOW`, `O_DIRECT` and `O_LARGEFILE` in
+/// another order, and `O_TMPFILE` includes `O_DIRECTORY`.
+#[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+const AARCH64_OPEN_FLAGS: Names = &[
+    (0o4010000, "O_SYNC"), (0o20040000, "O_TMPFILE"),
+    (0o100, "O_CREAT"), (0o200, "O_EXCL"), (0o400, "O_NOCTTY"), (0o1000, "O_TRUNC"), (0o2000, "O_APPEND"), (0o4000, "O_NONBLOCK"),
+    (0o10000, "O_DSYNC"), (0o20000, "O_ASYNC"), (0o40000, "O_DIRECTORY"),
+    (0o100000, "O_NOFOLLOW"), (0o200000, "O_DIRECT"), (0o400000, "O_LARGEFILE"),
+    (0o1000000, "O_NOATIME"), (0o2000000, "O_CLOEXEC"), (0o10000000, "O_PATH"),
+];
+
+const AT_FLAGS: Names = &[
+    (0x100, "AT_SYMLINK_NOFOLLOW"), (0x200, "AT_REMOVEDIR"), (0x400, "AT_SYMLINK_FOLLOW"),
+    (0x800, "AT_NO_AUTOMOUNT"), (0x1000, "AT_EMPTY_PATH"),
+];
+const FACCESSAT_FLAGS: Names = &[
+    (0x100, "AT_SYMLINK_NOFOLLOW"), (0x200, "AT_EACCESS"), (0x1000, "AT_EMPTY_PATH"),
+];
+const ACCESS_MODES: Names = &[(0x4, "R_OK"), (0x2, "W_OK"), (0x1, "X_OK")];
+
+const PROT_FLAGS: Names = &[
+    (0x1, "PROT_READ"), (0x2, "PROT_WRITE"), (0x4, "PROT_EXEC"), (0x8, "PROT_SEM"),
+    (0x1000000, "PROT_GROWSDOWN"), (0x2000000, "PROT_GROWSUP"),
+];
+const MAP_TYPE: u64 = 0xf;
+const MAP_TYPES: Names = &[(0x1, "MAP_SHARED"), (0x2, "MAP_PRIVATE"), (0x3, "MAP_SHARED_VALIDATE")];
+const MAP_FLAGS: Names = &[
+    (0x10, "MAP_FIXED"), (0x20, "MAP_ANONYMOUS"), (0x40, "MAP_32BIT"),
+    (0x100, "MAP_GROWSDOWN"), (0x800, "MAP_DENYWRITE"), (0x1000, "MAP_EXECUTABLE"),
+    (0x2000, "MAP_LOCKED"), (0x4000, "MAP_NORESERVE"), (0x8000, "MAP_POPULATE"),
+    (0x10000, "MAP_NONBLOCK"), (0x20000, "MAP_STACK"), (0x40000, "MAP_HUGETLB"),
+    (0x80000, "MAP_SYNC"), (0x100000, "MAP_FIXED_NOREPLACE"),
+];
+const MREMAP_FLAGS: Names = &[(0x1, "MREMAP_MAYMOVE"), (0x2, "MREMAP_FIXED"), (0x4, "MREMAP_DONTUNMAP")];
+const MSYNC_FLAGS: Names = &[(0x1, "MS_ASYNC"), (0x2, "MS_INVALIDATE"), (0x4, "MS_SYNC")];
+const MADVISE_ADVICE: Names = &[
+    (0, "MADV_NORMAL"), (1, "MADV_RANDOM"), (2, "MADV_SEQUENTIAL"), (3, "MADV_WILLNEED"),
+    (4, "MADV_DONTNEED"), (8, "MADV_FREE"), (9, "MADV_REMOVE"), (10, "MADV_DONTFORK"),
+    (11, "MADV_DOFORK"), (12, "MADV_MERGEABLE"), (13, "MADV_UNMERGEABLE"),
+    (14, "MADV_HUGEPAGE"), (15, "MADV_NOHUGEPAGE"), (16, "MADV_DONTDUMP"),
+    (17, "MADV_DODUMP"), (18, "MADV_WIPEONFORK"), (19, "MADV_KEEPONFORK"),
+    (20, "MADV_COLD"), (21, "MADV_PAGEOUT"),
+];
+
+const SEEK_WHENCE: Names = &[
+    (0, "SEEK_SET"), (1, "SEEK_CUR"), (2, "SEEK_END"), (3, "SEEK_DATA"), (4, "SEEK_HOLE"),
+];
+
+const SIGPROCMASK_HOW: Names = &[(0, "SIG_BLOCK"), (1, "SIG_UNBLOCK"), (2, "SIG_SETMASK")];
+
+const FCNTL_CMDS: Names = &[
+    (0, "F_DUPFD"), (1, "F_GETFD"), (2, "F_SETFD"), (3, "F_GETFL"), (4, "F_SETFL"),
+    (5, "F_GETLK"), (6, "F_SETLK"), (7, "F_SETLKW"), (8, "F_SETOWN"), (9, "F_GETOWN"),
+    (10, "F_SETSIG"), (11, "F_GETSIG"), (15, "F_SETOWN_EX"), (16, "F_GETOWN_EX"),
+    (36, "F_OFD_GETLK"), (37, "F_OFD_SETLK"), (38, "F_OFD_SETLKW"),
+    (1024, "F_SETLEASE"), (1025, "F_GETLEASE"), (1026, "F_NOTIFY"),
+    (1030, "F_DUPFD_CLOEXEC"), (1031, "F_SETPIPE_SZ"), (1032, "F_GETPIPE_SZ"),
+    (1033, "F_ADD_SEALS"), (1034, "F_GET_SEALS"),
+];
+const FD_FLAGS: Names = &[(1, "FD_CLOEXEC")];
+const FLOCK_OPS: Names = &[(1, "LOCK_SH"), (2, "LOCK_EX"), (4, "LOCK_NB"), (8, "LOCK_UN")];
+
+const SOCKET_DOMAINS: Names = &[
+    (0, "AF_UNSPEC"), (1, "AF_UNIX"), (2, "AF_INET"), (10, "AF_INET6"),
+    (16, "AF_NETLINK"), (17, "AF_PACKET"),
+];
+const FILE_TYPE: u64 = 0o170000;
+const FILE_TYPES: Names = &[
+    (0o140000, "S_IFSOCK"), (0o120000, "S_IFLNK"), (0o100000, "S_IFREG"), (0o060000, "S_IFBLK"),
+    (0o040000, "S_IFDIR"), (0o020000, "S_IFCHR"), (0o010000, "S_IFIFO"),
+];
+const POLL_EVENTS: Names = &[
+    (0x1, "POLLIN"), (0x2, "POLLPRI"), (0x4, "POLLOUT"), (0x8, "POLLERR"), (0x10, "POLLHUP"),
+    (0x20, "POLLNVAL"), (0x40, "POLLRDNORM"), (0x80, "POLLRDBAND"), (0x100, "POLLWRNORM"),
+    (0x200, "POLLWRBAND"), (0x2000, "POLLRDHUP"),
+];
+const SIGACTION_FLAGS: Names = &[
+    (0x1, "SA_NOCLDSTOP"), (0x2, "SA_NOCLDWAIT"), (0x4, "SA_SIGINFO"), (0x04000000, "SA_RESTORER"),
+    (0x08000000, "SA_ONSTACK"), (0x10000000, "SA_RESTART"), (0x40000000, "SA_NODEFER"),
+    (0x80000000, "SA_RESETHAND"),
+];
+const SOCKET_TYPE: u64 = 0xf;
+const SOCKET_TYPES: Names = &[
+    (1, "SOCK_STREAM"), (2, "SOCK_DGRAM"), (3, "SOCK_RAW"), (4, "SOCK_RDM"),
+    (5, "SOCK_SEQPACKET"), (10, "SOCK_PACKET"),
+];
+const SOCKET_FLAGS: Names = &[(0o4000, "SOCK_NONBLOCK"), (0o2000000, "SOCK_CLOEXEC")];
+const MSG_FLAGS: Names = &[
+    (0x1, "MSG_OOB"), (0x2, "MSG_PEEK"), (0x4, "MSG_DONTROUTE"), (0x8, "MSG_CTRUNC"),
+    (0x20, "MSG_TRUNC"), (0x40, "MSG_DONTWAIT"), (0x80, "MSG_EOR"), (0x100, "MSG_WAITALL"),
+    (0x2000, "MSG_ERRQUEUE"), (0x4000, "MSG_NOSIGNAL"), (0x8000, "MSG_MORE"),
+    (0x10000, "MSG_WAITFORONE"), (0x40000000, "MSG_CMSG_CLOEXEC"),
+];
+const SHUTDOWN_HOW: Names = &[(0, "SHUT_RD"), (1, "SHUT_WR"), (2, "SHUT_RDWR")];
+
+const CLONE_FLAGS: Names = &[
+    (0x100, "CLONE_VM"), (0x200, "CLONE_FS"), (0x400, "CLONE_FILES"),
+    (0x800, "CLONE_SIGHAND"), (0x1000, "CLONE_PIDFD"), (0x2000, "CLONE_PTRACE"),
+    (0x4000, "CLONE_VFORK"), (0x8000, "CLONE_PARENT"), (0x10000, "CLONE_THREAD"),
+    (0x20000, "CLONE_NEWNS"), (0x40000, "CLONE_SYSVSEM"), (0x80000, "CLONE_SETTLS"),
+    (0x100000, "CLONE_PARENT_SETTID"), (0x200000, "CLONE_CHILD_CLEARTID"),
+    (0x400000, "CLONE_DETACHED"), (0x800000, "CLONE_UNTRACED"),
+    (0x1000000, "CLONE_CHILD_SETTID"), (0x2000000, "CLONE_NEWCGROUP"),
+    (0x4000000, "CLONE_NEWUTS"), (0x8000000, "CLONE_NEWIPC"), (0x10000000, "CLONE_NEWUSER"),
+    (0x20000000, "CLONE_NEWPID"), (0x40000000, "CLONE_NEWNET"), (0x80000000, "CLONE_IO"),
+];
+const CLONE_SIGNAL: u64 = 0xff;
+
+const WAIT_OPTIONS: Names = &[
+    (0x1, "WNOHANG"), (0x2, "WUNTRACED"), (0x4, "WEXITED"), (0x8, "WCONTINUED"),
+    (0x1000000, "WNOWAIT"), (0x20000000, "__WNOTHREAD"), (0x40000000, "__WALL"),
+    (0x80000000, "__WCLONE"),
+];
+
+const FUTEX_CMD: u64 = 0x7f;
+const FUTEX_CMDS: Names = &[
+    (0, "FUTEX_WAIT"), (1, "FUTEX_WAKE"), (2, "FUTEX_FD"), (3, "FUTEX_REQUEUE"),
+    (4, "FUTEX_CMP_REQUEUE"), (5, "FUTEX_WAKE_OP"), (6, "FUTEX_LOCK_PI"),
+    (7, "FUTEX_UNLOCK_PI"), (8, "FUTEX_TRYLOCK_PI"), (9, "FUTEX_WAIT_BITSET"),
+    (10, "FUTEX_WAKE_BITSET"), (11, "FUTEX_WAIT_REQUEUE_PI"), (12, "FUTEX_CMP_REQUEUE_PI"),
+    (13, "FUTEX_LOCK_PI2"),
+];
+const FUTEX_FLAGS: Names = &[(128, "FUTEX_PRIVATE_FLAG"), (256, "FUTEX_CLOCK_REALTIME")];
+
+const ARCH_PRCTL_CODES: Names = &[
+    (0x1001, "ARCH_SET_GS"), (0x1002, "ARCH_SET_FS"), (0x1003, "ARCH_GET_FS"),
+    (0x1004, "ARCH_GET_GS"), (0x1011, "ARCH_GET_CPUID"), (0x1012, "ARCH_SET_CPUID"),
+    (0x3001, "ARCH_CET_STATUS"),
+];
+const PRCTL_OPTIONS: Names = &[
+    (1, "PR_SET_PDEATHSIG"), (2, "PR_GET_PDEATHSIG"), (3, "PR_GET_DUMPABLE"),
+    (4, "PR_SET_DUMPABLE"), (8, "PR_SET_KEEPCAPS"), (15, "PR_SET_NAME"), (16, "PR_GET_NAME"),
+    (21, "PR_GET_SECCOMP"), (22, "PR_SET_SECCOMP"), (23, "PR_CAPBSET_READ"),
+    (36, "PR_SET_CHILD_SUBREAPER"), (37, "PR_GET_CHILD_SUBREAPER"),
+    (38, "PR_SET_NO_NEW_PRIVS"), (39, "PR_GET_NO_NEW_PRIVS"), (0x59616d61, "PR_SET_PTRACER"),
+];
+
+const RLIMIT_RESOURCES: Names = &[
+    (0, "RLIMIT_CPU"), (1, "RLIMIT_FSIZE"), (2, "RLIMIT_DATA"), (3, "RLIMIT_STACK"),
+    (4, "RLIMIT_CORE"), (5, "RLIMIT_RSS"), (6, "RLIMIT_NPROC"), (7, "RLIMIT_NOFILE"),
+    (8, "RLIMIT_MEMLOCK"), (9, "RLIMIT_AS"), (10, "RLIMIT_LOCKS"), (11, "RLIMIT_SIGPENDING"),
+    (12, "RLIMIT_MSGQUEUE"), (13, "RLIMIT_NICE"), (14, "RLIMIT_RTPRIO"), (15, "RLIMIT_RTTIME"),
+];
+
+const CLOCK_IDS: Names = &[
+    (0, "CLOCK_REALTIME"), (1, "CLOCK_MONOTONIC"), (2, "CLOCK_PROCESS_CPUTIME_ID"),
+    (3, "CLOCK_THREAD_CPUTIME_ID"), (4, "CLOCK_MONOTONIC_RAW"), (5, "CLOCK_REALTIME_COARSE"),
+    (6, "CLOCK_MONOTONIC_COARSE"), (7, "CLOCK_BOOTTIME"), (8, "CLOCK_REALTIME_ALARM"),
+    (9, "CLOCK_BOOTTIME_ALARM"), (11, "CLOCK_TAI"),
+];
+const TIMER_FLAGS: Names = &[(1, "TIMER_ABSTIME")];
+
+const EPOLL_CTL_OPS: Names = &[(1, "EPOLL_CTL_ADD"), (2, "EPOLL_CTL_DEL"), (3, "EPOLL_CTL_MOD")];
+const GETRANDOM_FLAGS: Names = &[(1, "GRND_NONBLOCK"), (2, "GRND_RANDOM"), (4, "GRND_INSECURE")];
+
+const IOCTL_REQUESTS: Names = &[
+    (0x5401, "TCGETS"), (0x5402, "TCSETS"), (0x5403, "TCSETSW"), (0x5404, "TCSETSF"),
+    (0x540b, "TCFLSH"), (0x540e, "TIOCSCTTY"), (0x540f, "TIOCGPGRP"), (0x5410, "TIOCSPGRP"),
+    (0x5413, "TIOCGWINSZ"), (0x5414, "TIOCSWINSZ"), (0x541b, "FIONREAD"),
+    (0x5421, "FIONBIO"), (0x5450, "FIONCLEX"), (0x5451, "FIOCLEX"), (0x5452, "FIOASYNC"),
+];
+
+const SIGNAL_NAMES: [&str; 32] = [
+    "0", "SIGHUP", "SIGINT", "SIGQUIT", "SIGILL", "SIGTRAP", "SIGABRT", "SIGBUS",
+    "SIGFPE", "SIGKILL", "SIGUSR1", "SIGSEGV", "SIGUSR2", "SIGPIPE", "SIGALRM", "SIGTERM",
+    "SIGSTKFLT", "SIGCHLD", "SIGCONT", "SIGSTOP", "SIGTSTP", "SIGTTIN", "SIGTTOU", "SIGURG",
+    "SIGXCPU", "SIGXFSZ", "SIGVTALRM", "SIGPROF", "SIGWINCH", "SIGIO", "SIGPWR", "SIGSYS",
+];
+
+/// Render `value` as the names of the bits set in it joined by `|`. Bits
+/// without a name are kept as a trailing hex number. Names covering several
+/// bits must come before the single bit names they overlap.
+pub fn bitmask(value: u64, names: Names) -> String {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    for &(bits, name) in names {
+        if bits != 0 && rest & bits == bits {
+            parts.push(name.to_string());
+            rest &= !bits;
+        }
+    }
+    if rest != 0 || parts.is_empty() {
+        parts.push(format!("{:#x}", rest));
+    }
+    parts.join("|")
+}
+
+/// Render `value` as the name of the constant it equals, if there is one.
+pub fn constant(value: u64, names: Names) -> String {
+    names.iter()
+        .find(|&&(v, _)| v == value)
+        .map(|&(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("{:#x}", value))
+}
+
+/// A value made of an enumerated part under `mask` and flag bits above it.
+fn masked(value: u64, mask: u64, values: Names, bits: Names) -> String {
+    let mut s = constant(value & mask, values);
+    if value & !mask != 0 {
+        s = format!("{}|{}", s, bitmask(value & !mask, bits));
+    }
+    s
+}
+
+/// The `O_*` bits of `arch`.
+fn open_names(arch: Arch) -> Names {
+    match arch {
+        #[cfg(target_arch = "aarch64")]
+        Arch::Aarch64 => AARCH64_OPEN_FLAGS,
+        #[cfg(target_arch = "x86_64")]
+        _ => OPEN_FLAGS,
+    }
+}
+
+/// `O_TMPFILE`, which is several bits.
+fn o_tmpfile(names: Names) -> u64 {
+    names.iter().find(|&&(_, name)| name == "O_TMPFILE").map_or(0, |&(bits, _)| bits)
+}
+
+fn open_flags(value: u64, names: Names) -> String {
+    masked(value, OPEN_ACCMODE, OPEN_ACCESS, names)
+}
+
+pub fn mode(value: u64) -> String {
+    format!("0{:03o}", value)
+}
+
+/// `st_mode` style modes, the file type followed by the permission bits.
+pub fn file_mode(value: u64) -> String {
+    let perms = mode(value & !FILE_TYPE);
+    match value & FILE_TYPE {
+        0 => perms,
+        t => format!("{}|{}", constant(t, FILE_TYPES), perms),
+    }
+}
+
+pub fn address_family(value: u64) -> String {
+    constant(value, SOCKET_DOMAINS)
+}
+
+pub fn poll_events(value: u64) -> String {
+    optional_bitmask(value, POLL_EVENTS)
+}
+
+pub fn sigaction_flags(value: u64) -> String {
+    optional_bitmask(value, SIGACTION_FLAGS)
+}
+
+pub fn signal_name(sig: i32) -> String {
+    match sig {
+        1..=31 => SIGNAL_NAMES[sig as usize].to_string(),
+        32..=64 => format!("SIGRT_{}", sig - 32),
+        _ => format!("{}", sig),
+    }
+}
+
+fn dirfd(value: u64) -> String {
+    match value as i32 {
+        AT_FDCWD => "AT_FDCWD".to_string(),
+        fd => format!("{}", fd),
+    }
+}
+
+fn prot(value: u64) -> String {
+    if value == 0 { "PROT_NONE".to_string() } else { bitmask(value, PROT_FLAGS) }
+}
+
+fn access_mode(value: u64) -> String {
+    if value == 0 { "F_OK".to_string() } else { bitmask(value, ACCESS_MODES) }
+}
+
+fn clone_flags(value: u64) -> String {
+    let mut parts = Vec::new();
+    if value & !CLONE_SIGNAL != 0 {
+        parts.push(bitmask(value & !CLONE_SIGNAL, CLONE_FLAGS));
+    }
+    if value & CLONE_SIGNAL != 0 {
+        parts.push(signal_name((value & CLONE_SIGNAL) as i32));
+    }
+    if parts.is_empty() { "0".to_string() } else { parts.join("|") }
+}
+
+/// Flag arguments of the `*2`/`*4`/`*1` variants of calls that only take
+/// `O_CLOEXEC` and `O_NONBLOCK` style bits.
+fn cloexec_flags(value: u64, names: Names) -> String {
+    optional_bitmask(value, names)
+}
+
+fn optional_bitmask(value: u64, names: Names) -> String {
+    if value == 0 { "0".to_string() } else { bitmask(value, names) }
+}
+
+/// Whether argument `idx` of system call `name` of `arch` is left out of the
+/// trace because the call ignores it, as the mode of an `open` creating
+/// nothing.
+pub fn omitted(arch: Arch, name: &str, idx: usize, args: &[u64]) -> bool {
+    let flags = match (name, idx) {
+        ("open", 2) => args[1],
+        ("openat", 3) => args[2],
+        _ => return false,
+    };
+    let tmpfile = o_tmpfile(open_names(arch));
+    flags & O_CREAT == 0 && flags & tmpfile != tmpfile
+}
+
+/// Symbolic form of argument `idx` of system call `name` of `arch`, or
+/// `None` when the argument is better printed by its `ArgKind` alone.
+pub fn decode(arch: Arch, name: &str, idx: usize, args: &[u64]) -> Option<String> {
+    let value = args[idx];
+    let open = open_names(arch);
+    let s = match (name, idx) {
+        ("openat", 0) | ("mkdirat", 0) | ("mknodat", 0) | ("fchownat", 0) |
+        ("futimesat", 0) | ("newfstatat", 0) | ("unlinkat", 0) | ("renameat", 0) |
+        ("renameat", 2) | ("linkat", 0) | ("linkat", 2) | ("symlinkat", 1) |
+        ("readlinkat", 0) | ("fchmodat", 0) | ("faccessat", 0) | ("utimensat", 0) |
+        ("name_to_handle_at", 0) | ("renameat2", 0) | ("renameat2", 2) |
+        ("execveat", 0) | ("fchmodat2", 0) | ("setxattrat", 0) | ("getxattrat", 0) |
+        ("listxattrat", 0) | ("removexattrat", 0) => dirfd(value),
+
+        ("open", 1) | ("openat", 2) => open_flags(value, open),
+        ("open", 2) | ("openat", 3) | ("creat", 1) | ("mkdir", 1) | ("mkdirat", 2) |
+        ("chmod", 1) | ("fchmod", 1) | ("fchmodat", 2) | ("fchmodat2", 2) | ("mknod", 1) | ("mknodat", 2) |
+        ("umask", 0) => mode(value),
+        ("newfstatat", 3) | ("unlinkat", 2) | ("fchownat", 4) | ("linkat", 4) |
+        ("utimensat", 3) | ("fchmodat", 3) | ("fchmodat2", 3) | ("execveat", 4) |
+        ("setxattrat", 2) | ("getxattrat", 2) | ("listxattrat", 2) | ("removexattrat", 2) |
+        ("name_to_handle_at", 4) => optional_bitmask(value, AT_FLAGS),
+        ("access", 1) | ("faccessat", 2) => access_mode(value),
+        ("faccessat", 3) => optional_bitmask(value, FACCESSAT_FLAGS),
+        ("lseek", 2) => constant(value, SEEK_WHENCE),
+        ("fcntl", 1) => constant(value, FCNTL_CMDS),
+        ("fcntl", 2) => fcntl_arg(args[1], value, open)?,
+        ("flock", 1) => bitmask(value, FLOCK_OPS),
+        ("pipe2", 1) | ("dup3", 2) | ("accept4", 3) | ("eventfd2", 1) |
+        ("epoll_create1", 0) | ("inotify_init1", 0) | ("signalfd4", 3) |
+        ("timerfd_create", 1) | ("memfd_create", 1) => cloexec_flags(value, open),
+
+        // The i386 mmap takes a single pointer to its arguments, and has
+        // no third one; mmap2 takes them as the others do.
+        ("mmap", 2) | ("mmap2", 2) | ("mprotect", 2) | ("pkey_mprotect", 2) => prot(value),
+        ("mmap", 3) | ("mmap2", 3) => masked(value, MAP_TYPE, MAP_TYPES, MAP_FLAGS),
+        ("mremap", 3) => optional_bitmask(value, MREMAP_FLAGS),
+        ("msync", 2) => bitmask(value, MSYNC_FLAGS),
+        ("madvise", 2) => constant(value, MADVISE_ADVICE),
+
+        ("kill", 1) | ("tkill", 1) | ("tgkill", 2) | ("rt_sigaction", 0) |
+        ("rt_sigqueueinfo", 1) | ("rt_tgsigqueueinfo", 2) => signal_name(value as i32),
+        ("rt_sigprocmask", 0) => constant(value, SIGPROCMASK_HOW),
+
+        ("socket", 0) | ("socketpair", 0) => constant(value, SOCKET_DOMAINS),
+        ("socket", 1) | ("socketpair", 1) =>
+            masked(value, SOCKET_TYPE, SOCKET_TYPES, SOCKET_FLAGS),
+        ("sendto", 3) | ("recvfrom", 3) | ("sendmsg", 2) | ("recvmsg", 2) |
+        ("sendmmsg", 3) | ("recvmmsg", 3) => optional_bitmask(value, MSG_FLAGS),
+        ("shutdown", 1) => constant(value, SHUTDOWN_HOW),
+        ("epoll_ctl", 1) => constant(value, EPOLL_CTL_OPS),
+
+        ("clone", 0) => clone_flags(value),
+        ("wait4", 2) | ("waitid", 3) => optional_bitmask(value, WAIT_OPTIONS),
+        ("futex", 1) => masked(value, FUTEX_CMD, FUTEX_CMDS, FUTEX_FLAGS),
+        ("arch_prctl", 0) => constant(value, ARCH_PRCTL_CODES),
+        ("prctl", 0) => constant(value, PRCTL_OPTIONS),
+        ("getrlimit", 0) | ("setrlimit", 0) | ("prlimit64", 1) =>
+            constant(value, RLIMIT_RESOURCES),
+        ("clock_gettime", 0) | ("clock_settime", 0) | ("clock_getres", 0) |
+        ("clock_nanosleep", 0) | ("clock_adjtime", 0) | ("timer_create", 0) |
+        ("timerfd_create", 0) => constant(value, CLOCK_IDS),
+        ("clock_nanosleep", 1) => optional_bitmask(value, TIMER_FLAGS),
+        ("getrandom", 2) => optional_bitmask(value, GETRANDOM_FLAGS),
+        ("ioctl", 1) => constant(value, IOCTL_REQUESTS),
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// The third argument of `fcntl`, whose meaning depends on the command.
+fn fcntl_arg(cmd: u64, value: u64, open: Names) -> Option<String> {
+    match cmd {
+        2 => Some(optional_bitmask(value, FD_FLAGS)),
+        4 => Some(open_flags(value, open)),
+        _ => None,
+    }
+}
+
+#[test]
+fn open_flags_test() {
+    let native = Arch::native();
+    assert_eq!(open_flags(0o2000000, OPEN_FLAGS), "O_RDONLY|O_CLOEXEC");
+    assert_eq!(open_flags(0o1101, OPEN_FLAGS), "O_WRONLY|O_CREAT|O_TRUNC");
+    assert_eq!(decode(native, "openat", 0, &[0xffffff9c]), Some("AT_FDCWD".to_string()));
+    assert!(omitted(native, "openat", 3, &[0, 0, 0o2000000, 0]));
+    assert!(!omitted(native, "openat", 3, &[0, 0, 0o1101, 0o644]));
+    assert!(!omitted(native, "open", 2, &[0, o_tmpfile(open_names(native)) | 2, 0o600]));
+}
+
+#[test]
+fn arch_flags_test() {
+    // The same bits mean other flags on aarch64.
+    assert_eq!(open_flags(0o240000, OPEN_FLAGS), "O_RDONLY|O_DIRECT|O_DIRECTORY");
+    assert_eq!(open_flags(0o240000, AARCH64_OPEN_FLAGS), "O_RDONLY|O_DIRECTORY|O_DIRECT");
+    assert_eq!(open_flags(0o20040002, AARCH64_OPEN_FLAGS), "O_RDWR|O_TMPFILE");
+    assert_eq!(o_tmpfile(AARCH64_OPEN_FLAGS), 0o20040000);
+    #[cfg(target_arch = "aarch64")]
+    assert_eq!(decode(Arch::Aarch64, "openat", 2, &[0, 0, 0o500000]),
+               Some("O_RDONLY|O_NOFOLLOW|O_LARGEFILE".to_string()));
+    #[cfg(target_arch = "x86_64")]
+    {
+        // 32 bit programs open files with O_LARGEFILE, and map memory with
+        // mmap2.
+        assert_eq!(decode(Arch::I386, "openat", 2, &[0, 0, 0o2100000]),
+                   Some("O_RDONLY|O_LARGEFILE|O_CLOEXEC".to_string()));
+        assert_eq!(decode(Arch::I386, "mmap2", 3, &[0, 0, 0, 0x22]),
+                   Some("MAP_PRIVATE|MAP_ANONYMOUS".to_string()));
+        let (nr, _) = Arch::I386.syscalls().find(|&(_, name)| name == "mmap").unwrap();
+        assert_eq!(Arch::I386.syscall_args(nr).len(), 1);
+    }
+}
+
+#[test]
+fn mmap_flags_test() {
+    assert_eq!(decode(Arch::native(), "mmap", 2, &[0, 0, 3]), Some("PROT_READ|PROT_WRITE".to_string()));
+    assert_eq!(decode(Arch::native(), "mmap", 2, &[0, 0, 0]), Some("PROT_NONE".to_string()));
+    assert_eq!(decode(Arch::native(), "mmap", 3, &[0, 0, 0, 0x22]),
+               Some("MAP_PRIVATE|MAP_ANONYMOUS".to_string()));
+    assert_eq!(decode(Arch::native(), "mmap", 3, &[0, 0, 0, 0x400002]),
+               Some("MAP_PRIVATE|0x400000".to_string()));
+}
+
+#[test]
+fn signal_name_test() {
+    assert_eq!(decode(Arch::native(), "kill", 1, &[0, 15]), Some("SIGTERM".to_string()));
+    assert_eq!(signal_name(34), "SIGRT_2");
+    assert_eq!(decode(Arch::native(), "clone", 0, &[0x1200011]),
+               Some("CLONE_CHILD_CLEARTID|CLONE_CHILD_SETTID|SIGCHLD".to_string()));
+}
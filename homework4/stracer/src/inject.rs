@@ -0,0 +1,134 @@
+//! Fault injection: make selected system calls fail with an error, or return
+//! a value, without running them. Specs look like strace's
+//! `-e inject=SET:error=ENOSPC:when=3+`, for example `write:error=ENOSPC`,
+//! `read:error=EINTR:when=2` or `%file:retval=0:when=1+2`.
+
+use crate::errno;
+use crate::filter;
+use std::str::FromStr;
+
+/// What a call we inject into returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// Fail with this errno.
+    Error(i64),
+    /// Succeed with this value.
+    Retval(i64),
+}
+
+impl Fault {
+    /// The raw value the kernel would leave in the return register.
+    pub fn retval(self) -> i64 {
+        match self {
+            Fault::Error(errno) => -errno,
+            Fault::Retval(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Injection {
+    syscalls: Vec<String>,
+    fault: Fault,
+    /// Inject into the `first`th matching call (counting from 1)...
+    first: u64,
+    /// ...and then into every `step`th call after it, or no more if `None`.
+    step: Option<u64>,
+    /// Matching calls seen so far, across all traced processes.
+    seen: u64,
+}
+
+impl Injection {
+    /// Whether calls to `name` are among those the spec picks from.
+    pub fn selects(&self, name: &str) -> bool {
+        self.syscalls.iter().any(|s| s == name)
+    }
+
+    /// Count a call to `name`, returning the fault to inject if this is one
+    /// of the calls the spec selects.
+    pub fn check(&mut self, name: &str) -> Option<Fault> {
+        if !self.selects(name) {
+            return None;
+        }
+        self.seen += 1;
+        let hit = match self.step {
+            _ if self.seen < self.first => false,
+            None => self.seen == self.first,
+            Some(step) => (self.seen - self.first).is_multiple_of(step),
+        };
+        if hit { Some(self.fault) } else { None }
+    }
+}
+
+fn parse_number(s: &str) -> Result<u64, String> {
+    s.parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("invalid number {} in when=", s))
+}
+
+impl FromStr for Injection {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Injection, String> {
+        let mut parts = spec.split(':');
+        let syscalls = filter::parse_set(parts.next().unwrap_or(""))?;
+        let mut fault = None;
+        let (mut first, mut step) = (1, Some(1));
+        for part in parts {
+            let (key, value) = match part.find('=') {
+                Some(idx) => (&part[..idx], &part[idx + 1..]),
+                None => return Err(format!("invalid injection {}", part)),
+            };
+            match key {
+                "error" => {
+                    let err = errno::from_name(value)
+                        .or_else(|| value.parse::<i64>().ok())
+                        .filter(|&e| e > 0 && e < errno::MAX_ERRNO)
+                        .ok_or_else(|| format!("invalid error {}", value))?;
+                    fault = Some(Fault::Error(err));
+                },
+                "retval" => {
+                    let retval = value.parse::<i64>()
+                        .map_err(|_| format!("invalid retval {}", value))?;
+                    fault = Some(Fault::Retval(retval));
+                },
+                "when" => {
+                    let (n, s) = match value.find('+') {
+                        Some(idx) => (&value[..idx], Some(&value[idx + 1..])),
+                        None => (value, None),
+                    };
+                    first = parse_number(n)?;
+                    step = match s {
+                        None => None,
+                        Some("") => Some(1),
+                        Some(s) => Some(parse_number(s)?),
+                    };
+                },
+                _ => return Err(format!("invalid injection {}", part)),
+            }
+        }
+        let fault = fault.ok_or_else(|| "injection needs error= or retval=".to_string())?;
+        Ok(Injection { syscalls, fault, first, step, seen: 0 })
+    }
+}
+
+#[test]
+fn injection_test() {
+    let mut every = "write:error=ENOSPC".parse::<Injection>().unwrap();
+    assert_eq!(every.check("read"), None);
+    assert_eq!(every.check("write"), Some(Fault::Error(28)));
+    assert_eq!(every.check("write"), Some(Fault::Error(28)));
+
+    let mut second = "read:error=EINTR:when=2".parse::<Injection>().unwrap();
+    let hits = (0..4).map(|_| second.check("read").is_some()).collect::<Vec<bool>>();
+    assert_eq!(hits, vec![false, true, false, false]);
+
+    let mut every_third = "read:retval=0:when=1+3".parse::<Injection>().unwrap();
+    let hits = (0..5).map(|_| every_third.check("read").is_some()).collect::<Vec<bool>>();
+    assert_eq!(hits, vec![true, false, false, true, false]);
+    assert_eq!(Fault::Retval(0).retval(), 0);
+
+    assert!("write".parse::<Injection>().is_err());
+    assert!("write:error=ENOPE".parse::<Injection>().is_err());
+}
@@ -0,0 +1,44 @@
+//! Just enough JSON writing for the `--format json` output.
+
+/// `s` as a quoted JSON string.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn array<T, F: Fn(&T) -> String>(items: &[T], f: F) -> String {
+    format!("[{}]", items.iter().map(f).collect::<Vec<String>>().join(","))
+}
+
+/// An object from already encoded values, keeping the order of `fields`.
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields.iter()
+        .map(|(key, value)| format!("{}:{}", string(key), value))
+        .collect::<Vec<String>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+pub fn optional<T, F: Fn(&T) -> String>(value: &Option<T>, f: F) -> String {
+    value.as_ref().map(f).unwrap_or_else(|| "null".to_string())
+}
+
+#[test]
+fn json_test() {
+    assert_eq!(string("a\"b\\\n\u{1}"), "\"a\\\"b\\\\\\n\\u0001\"");
+    assert_eq!(object(&[("a", array(&[1, 2], |n| n.to_string())),
+                        ("b", optional(&None::<i32>, |n| n.to_string()))]),
+               "{\"a\":[1,2],\"b\":null}");
+}
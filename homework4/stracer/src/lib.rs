@@ -0,0 +1,78 @@
+//! A system call tracer in the spirit of strace, as a library: trace a
+//! command or a running process and receive what it does as typed events.
+//!
+//! ```no_run
+//! use stracer::events::Event;
+//! use stracer::tracer::Tracer;
+//!
+//! let status = Tracer::new()
+//!     .filter("trace=%file").unwrap()
+//!     .spawn("make", &["make", "-j4"], |event| {
+//!         if let Event::Syscall(call) = event {
+//!             println!("{}", call.to_text());
+//!         }
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! The `stracer` binary is the command line front end to the same tracer.
+
+pub mod arch;
+mod buffers;
+pub mod command;
+mod dwarf;
+mod elf;
+mod errno;
+pub mod events;
+mod fds;
+pub mod filter;
+mod flags;
+pub mod inject;
+mod json;
+mod memory;
+pub mod output;
+mod seccomp;
+mod signals;
+pub mod stack;
+mod structs;
+mod summary;
+pub mod system_call_args;
+mod system_call_names;
+pub mod timestamps;
+pub mod trace;
+pub mod tracer;
+mod tree;
+mod util;
+
+use filter::Status;
+use inject::Injection;
+use output::{Format, Output};
+use timestamps::Timestamps;
+
+#[derive(Debug)]
+pub enum TraceOption {
+    Ignore(Vec<String>),
+    Trace(Vec<String>),
+}
+
+/// How the trace should be reported, built from the command line or by a
+/// `Tracer`.
+#[derive(Debug)]
+pub struct TraceConfig {
+    pub topt: TraceOption,
+    pub status: Status,
+    pub summary: bool,
+    pub output: Output,
+    pub format: Format,
+    pub timestamps: Option<Timestamps>,
+    pub syscall_times: bool,
+    pub injections: Vec<Injection>,
+    pub string_limit: usize,
+    pub hex_dump: Vec<i32>,
+    pub decode_fds: bool,
+    pub tree: bool,
+    pub follow_forks: bool,
+    pub seccomp_bpf: bool,
+    pub stack_trace: bool,
+}
+
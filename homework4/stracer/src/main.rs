@@ -1,36 +1,103 @@
 
 mod args;
-mod trace;
-mod util;
-mod system_call_names;
 
 use structopt::StructOpt;
+use structopt::clap::{Error, ErrorKind};
 use args::Opt;
-use trace::*;
+use stracer::{TraceConfig, TraceOption};
+use stracer::command::Command;
+use stracer::filter::{self, Status};
+use stracer::output::Output;
+use stracer::trace::*;
 use std::ffi::CString;
-
-#[derive(Debug)]
-pub enum TraceOption {
-    Ignore(Vec<String>),
-    Trace(Vec<String>),
-}
+use std::process;
+use libc::c_int;
+use nix::sys::signal::SigSet;
+use nix::unistd::Pid;
+use log::info;
 
 fn main() {
     env_logger::init();
     let mut opt = Opt::from_args();
-    println!("{:?}", opt);
+    info!("{:?}", opt);
 
-    opt.exe_args.insert(0, opt.exe.clone());
-    let exe_c = CString::new(opt.exe).unwrap();
-    let exe_args = opt.exe_args.iter()
-                               .map(|s| CString::new(s.as_str()).unwrap())
-                               .collect::<Vec<CString>>();
-    let topt = if let Some(v) = opt.dont_trace {
+    let mut topt = if let Some(v) = opt.dont_trace {
         TraceOption::Ignore(v)
     } else if let Some(v) = opt.to_trace {
         TraceOption::Trace(v)
     } else {
-        panic!("Trace option error");
+        TraceOption::Ignore(Vec::new())
+    };
+    let mut status = Status::All;
+    for expr in &opt.expr {
+        if let Err(e) = filter::apply(expr, &mut topt, &mut status) {
+            Error::with_description(&e, ErrorKind::InvalidValue).exit();
+        }
+    }
+    let output = Output::new(opt.output, opt.output_separately)
+        .expect("Failed to open output file");
+    let config = TraceConfig {
+        topt,
+        status,
+        summary: opt.summary,
+        output,
+        format: opt.format,
+        timestamps: opt.timestamps,
+        syscall_times: opt.syscall_times,
+        injections: opt.inject,
+        string_limit: opt.string_limit,
+        hex_dump: opt.hex_dump,
+        decode_fds: opt.decode_fds,
+        tree: opt.tree,
+        follow_forks: !opt.no_follow_forks,
+        seccomp_bpf: opt.seccomp_bpf,
+        stack_trace: opt.stack_trace,
     };
-    trace(&exe_c, &exe_args, topt).unwrap();
+    if let Some(pid) = opt.pid {
+        if let Err(e) = attach(Pid::from_raw(pid), config, &mut |_| ()) {
+            fail(e);
+        }
+        return;
+    }
+
+    let exe = opt.exe.unwrap();
+    opt.exe_args.insert(0, exe.clone());
+    let exe_c = CString::new(exe).unwrap();
+    let exe_args = opt.exe_args.iter()
+                               .map(|s| CString::new(s.as_str()).unwrap())
+                               .collect::<Vec<CString>>();
+    let command = Command { user: opt.user, env: opt.env, directory: opt.directory };
+    match trace(&exe_c, &exe_args, &command, config, &mut |_| ()) {
+        Ok(Some(status)) => exit_like(status),
+        Ok(None) => (),
+        Err(e) => fail(e),
+    }
+}
+
+fn fail(error: nix::Error) -> ! {
+    eprintln!("stracer: {}", error);
+    process::exit(1)
+}
+
+/// End the way the traced command did, so scripts running it under us see
+/// no difference: with its exit code, or killed by the same signal.
+fn exit_like(status: ExitStatus) -> ! {
+    match status {
+        ExitStatus::Exited(code) => process::exit(code),
+        ExitStatus::Killed(signal, _) => {
+            // The tracee dumped core already if it was going to, we don't.
+            let no_core = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+            unsafe {
+                libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+                libc::signal(signal as c_int, libc::SIG_DFL);
+            }
+            let mut set = SigSet::empty();
+            set.add(signal);
+            let _ = set.thread_unblock();
+            let _ = nix::sys::signal::raise(signal);
+            // Signals which don't kill us by default, as a shell reports
+            // them.
+            process::exit(128 + signal as i32)
+        },
+    }
 }
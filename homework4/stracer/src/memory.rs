@@ -0,0 +1,185 @@
+//! Reading the memory of a tracee. `process_vm_readv` copies a whole range
+//! in one call; where it is not allowed we fall back to reading
+//! `/proc/PID/mem`, and then to `PTRACE_PEEKDATA` a word at a time.
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use libc::{c_void, iovec};
+use nix::errno::Errno;
+use nix::sys::ptrace::{self, Request};
+use nix::unistd::Pid;
+use std::cmp::min;
+use std::fs::File;
+use std::io;
+use std::mem::{self, MaybeUninit};
+use std::os::unix::fs::FileExt;
+use std::ptr;
+
+const PAGE_SIZE: u64 = 4096;
+/// How much `read_bytes` reads at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Errors meaning a way of reading is not available, rather than that the
+/// memory is not mapped.
+const FALLBACK_ERRORS: [Errno; 4] = [Errno::ENOSYS, Errno::EPERM, Errno::EACCES, Errno::ENOENT];
+
+/// Longest string read for a path or other NUL terminated argument.
+pub const PATH_MAX: usize = 4096;
+
+fn io_error(e: io::Error) -> nix::Error {
+    nix::Error::Sys(Errno::from_i32(e.raw_os_error().unwrap_or(Errno::EIO as i32)))
+}
+
+fn vm_readv(pid: Pid, address: u64, buf: &mut [u8]) -> nix::Result<usize> {
+    let local = iovec { iov_base: buf.as_mut_ptr() as *mut c_void, iov_len: buf.len() };
+    let remote = iovec { iov_base: address as *mut c_void, iov_len: buf.len() };
+    let n = unsafe { libc::process_vm_readv(pid.into(), &local, 1, &remote, 1, 0) };
+    if n < 0 {
+        Err(nix::Error::Sys(Errno::last()))
+    } else {
+        Ok(n as usize)
+    }
+}
+
+fn proc_mem(pid: Pid, address: u64, buf: &mut [u8]) -> nix::Result<usize> {
+    let file = File::open(format!("/proc/{}/mem", pid)).map_err(io_error)?;
+    file.read_at(buf, address).map_err(io_error)
+}
+
+fn peek_data(pid: Pid, address: u64, buf: &mut [u8]) -> nix::Result<usize> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(buf.len() + 8);
+    while bytes.len() < buf.len() {
+        let res = unsafe {
+            #[allow(deprecated)]
+            ptrace::ptrace(Request::PTRACE_PEEKDATA,
+                           pid,
+                           (address + bytes.len() as u64) as *mut c_void,
+                           ptr::null_mut())
+        };
+        match res {
+            Ok(word) => bytes.write_i64::<LittleEndian>(word).unwrap(),
+            Err(_) if !bytes.is_empty() => break,
+            Err(e) => return Err(e),
+        }
+    }
+    let n = min(bytes.len(), buf.len());
+    buf[..n].copy_from_slice(&bytes[..n]);
+    Ok(n)
+}
+
+/// Read as much of `buf` as is mapped from `address` on, returning how many
+/// bytes were read. Only fails if not even the first byte could be read.
+pub fn read_partial(pid: Pid, address: u64, buf: &mut [u8]) -> nix::Result<usize> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    match vm_readv(pid, address, buf) {
+        Err(nix::Error::Sys(e)) if FALLBACK_ERRORS.contains(&e) => (),
+        result => return result,
+    }
+    match proc_mem(pid, address, buf) {
+        Ok(0) => Err(nix::Error::Sys(Errno::EFAULT)),
+        // The file may not be there, or may be forbidden by the security model.
+        Err(nix::Error::Sys(e)) if FALLBACK_ERRORS.contains(&e) => peek_data(pid, address, buf),
+        result => result,
+    }
+}
+
+/// Read exactly `len` bytes at `address` in the tracee. `len` may come from
+/// the tracee, so the bytes are read a chunk at a time rather than into a
+/// buffer of that size up front.
+pub fn read_bytes(pid: Pid, address: u64, len: usize) -> nix::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; min(len, CHUNK_SIZE)];
+    while bytes.len() < len {
+        let want = min(len - bytes.len(), CHUNK_SIZE);
+        match read_partial(pid, address.wrapping_add(bytes.len() as u64), &mut chunk[..want])? {
+            0 => return Err(nix::Error::Sys(Errno::EFAULT)),
+            n => bytes.extend_from_slice(&chunk[..n]),
+        }
+    }
+    Ok(bytes)
+}
+
+/// Read a NUL terminated string at `address` in the tracee, without the NUL,
+/// and whether it went on past `limit` bytes. A string running into unmapped
+/// memory ends there.
+pub fn read_string(pid: Pid, address: u64, limit: usize) -> nix::Result<(Vec<u8>, bool)> {
+    let mut string = Vec::new();
+    // Never ask for more than the rest of a page, the next may not be mapped.
+    let mut chunk = [0; PAGE_SIZE as usize];
+    loop {
+        let at = address + string.len() as u64;
+        let len = min(PAGE_SIZE - at % PAGE_SIZE, (limit + 1 - string.len()) as u64);
+        let n = match read_partial(pid, at, &mut chunk[..len as usize]) {
+            Ok(n) => n,
+            Err(_) if !string.is_empty() => return Ok((string, false)),
+            Err(e) => return Err(e),
+        };
+        if let Some(end) = chunk[..n].iter().position(|&b| b == 0) {
+            string.extend_from_slice(&chunk[..end]);
+            return Ok((string, false));
+        }
+        string.extend_from_slice(&chunk[..n]);
+        if string.len() > limit {
+            string.truncate(limit);
+            return Ok((string, true));
+        }
+        if n < len as usize {
+            return Ok((string, false));
+        }
+    }
+}
+
+/// Read a `T` laid out at `address` in the tracee.
+pub fn read_struct<T: Copy>(pid: Pid, address: u64) -> nix::Result<T> {
+    let bytes = read_bytes(pid, address, mem::size_of::<T>())?;
+    let mut value = MaybeUninit::<T>::uninit();
+    unsafe {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), value.as_mut_ptr() as *mut u8, bytes.len());
+        Ok(value.assume_init())
+    }
+}
+
+/// Read a NULL terminated array of pointers `word_size` bytes wide, without
+/// the NULL. At most `max` pointers are read.
+pub fn read_pointer_array(pid: Pid, address: u64, word_size: usize, max: usize)
+                          -> nix::Result<Vec<u64>> {
+    let mut pointers = Vec::new();
+    while pointers.len() < max {
+        let bytes = read_bytes(pid, address + (pointers.len() * word_size) as u64, word_size)?;
+        let pointer = bytes.iter().rev().fold(0, |word, &b| (word << 8) | u64::from(b));
+        if pointer == 0 {
+            break;
+        }
+        pointers.push(pointer);
+    }
+    Ok(pointers)
+}
+
+/// Read a NULL terminated array of pointers to strings, such as the `argv` of
+/// `execve`, where pointers are `word_size` bytes. At most `max` strings are
+/// read, each up to `PATH_MAX` bytes.
+pub fn read_string_array(pid: Pid, address: u64, word_size: usize, max: usize)
+                         -> nix::Result<Vec<Vec<u8>>> {
+    read_pointer_array(pid, address, word_size, max)?.into_iter()
+        .map(|pointer| read_string(pid, pointer, PATH_MAX).map(|(string, _)| string))
+        .collect()
+}
+
+#[test]
+fn read_self_test() {
+    let pid = nix::unistd::getpid();
+    let data = *b"some bytes\0more";
+    let address = data.as_ptr() as u64;
+    assert_eq!(read_bytes(pid, address, 4).unwrap(), b"some");
+    assert_eq!(read_string(pid, address, 100).unwrap(), (b"some bytes".to_vec(), false));
+    assert_eq!(read_string(pid, address, 4).unwrap(), (b"some".to_vec(), true));
+    assert_eq!(read_struct::<[u8; 2]>(pid, address + 5).unwrap(), *b"by");
+    assert!(read_bytes(pid, 8, 4).is_err());
+    let strings = *b"one\0two\0";
+    let array = [strings.as_ptr() as u64, strings[4..].as_ptr() as u64, 0];
+    assert_eq!(read_string_array(pid, array.as_ptr() as u64, 8, 10).unwrap(),
+               vec![b"one".to_vec(), b"two".to_vec()]);
+    assert_eq!(read_string_array(pid, array.as_ptr() as u64, 8, 1).unwrap().len(), 1);
+    assert_eq!(read_pointer_array(pid, array.as_ptr() as u64, 8, 10).unwrap(), array[..2].to_vec());
+}
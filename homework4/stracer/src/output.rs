@@ -0,0 +1,266 @@
+use crate::util;
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// What trace lines look like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// strace style `name(args) = retval` lines.
+    Text,
+    /// One JSON object per line and call.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown output format {}", s)),
+        }
+    }
+}
+
+/// Where trace lines go, like strace's `-o` and `-ff`.
+#[derive(Debug)]
+enum Sink {
+    /// Nowhere, for library users who take the events instead.
+    Discard,
+    Stdout,
+    File(LineWriter<File>),
+    /// One `PREFIX.PID` file per traced process, opened as processes show up
+    /// and closed once they are gone. Lines in these files don't carry the
+    /// pid, the file name does.
+    PerProcess {
+        prefix: PathBuf,
+        files: HashMap<Pid, LineWriter<File>>,
+        /// The pids we created a file for, a new process reusing one of them
+        /// adds to it.
+        created: HashSet<Pid>,
+    },
+}
+
+/// The trace output. A call's line may be started when the call is entered
+/// and finished when it returns; if other lines have to be written in
+/// between, it is cut short with ` <unfinished ...>` as strace does.
+#[derive(Debug)]
+pub struct Output {
+    sink: Sink,
+    /// The pids whose lines were started and are waiting for their calls to
+    /// return. Only one when all pids share the output.
+    unfinished: HashSet<Pid>,
+    /// The process each thread we wrote about belongs to.
+    groups: HashMap<Pid, Pid>,
+}
+
+impl Output {
+    pub fn new(path: Option<PathBuf>, separately: bool) -> io::Result<Output> {
+        let sink = match path {
+            None => Sink::Stdout,
+            Some(prefix) => if separately {
+                Sink::PerProcess { prefix, files: HashMap::new(), created: HashSet::new() }
+            } else {
+                Sink::File(LineWriter::new(File::create(prefix)?))
+            },
+        };
+        Ok(Output { sink, unfinished: HashSet::new(), groups: HashMap::new() })
+    }
+
+    /// An output which writes nothing.
+    pub fn discard() -> Output {
+        Output { sink: Sink::Discard, unfinished: HashSet::new(), groups: HashMap::new() }
+    }
+
+    /// Write one line of the trace of `pid`, marked with the pid unless the
+    /// file name already says which process it is about.
+    pub fn write_line(&mut self, pid: Pid, line: &str) -> io::Result<()> {
+        let line = self.mark(pid, line);
+        self.write_raw(pid, &line)
+    }
+
+    /// Write one line about `pid` exactly as given.
+    pub fn write_raw(&mut self, pid: Pid, line: &str) -> io::Result<()> {
+        self.interrupt(pid)?;
+        self.write(pid, &format!("{}\n", line))
+    }
+
+    /// Start the line of a call `pid` is entering, to be finished with
+    /// `finish_line` once it returns.
+    pub fn start_line(&mut self, pid: Pid, text: &str) -> io::Result<()> {
+        self.interrupt(pid)?;
+        let text = self.mark(pid, text);
+        self.write(pid, &text)?;
+        self.unfinished.insert(pid);
+        Ok(())
+    }
+
+    /// Finish the line `pid` started, if nothing was written in between.
+    /// Returns false when it was cut short and the rest needs a line of its
+    /// own.
+    pub fn finish_line(&mut self, pid: Pid, text: &str) -> io::Result<bool> {
+        if !self.unfinished.remove(&pid) {
+            return Ok(false);
+        }
+        self.write(pid, &format!("{}\n", text))?;
+        Ok(true)
+    }
+
+    /// Cut short every started line, as when tracing stops.
+    pub fn interrupt_all(&mut self) -> io::Result<()> {
+        for pid in self.unfinished.drain().collect::<Vec<Pid>>() {
+            self.write(pid, " <unfinished ...>\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write text that is not about a single process, such as the summary
+    /// table. With one file per process it goes to the file named `PREFIX`.
+    pub fn write_report(&mut self, text: &str) -> io::Result<()> {
+        self.interrupt_all()?;
+        match &mut self.sink {
+            Sink::Discard => Ok(()),
+            Sink::Stdout => io::stdout().write_all(text.as_bytes()),
+            Sink::File(file) => file.write_all(text.as_bytes()),
+            Sink::PerProcess { prefix, .. } => File::create(prefix)?.write_all(text.as_bytes()),
+        }
+    }
+
+    /// `pid` is gone, a new task may get its id. Its own file, if it has
+    /// one, is closed.
+    pub fn forget(&mut self, pid: Pid) {
+        self.groups.remove(&pid);
+        if let Sink::PerProcess { files, .. } = &mut self.sink {
+            files.remove(&pid);
+        }
+    }
+
+    /// `[PID]: ` for the main thread of a process, and `[PID/TID]: ` for its
+    /// other threads.
+    fn mark(&mut self, pid: Pid, text: &str) -> String {
+        if let Sink::PerProcess { .. } | Sink::Discard = self.sink {
+            return text.to_string();
+        }
+        match *self.groups.entry(pid).or_insert_with(|| util::thread_group(pid)) {
+            group if group == pid => format!("[{}]: {}", pid, text),
+            group => format!("[{}/{}]: {}", group, pid, text),
+        }
+    }
+
+    /// Cut short the started lines a line from `pid` would come after.
+    fn interrupt(&mut self, pid: Pid) -> io::Result<()> {
+        let cut = match self.sink {
+            Sink::PerProcess { .. } => self.unfinished.take(&pid),
+            _ => self.unfinished.drain().next(),
+        };
+        match cut {
+            Some(cut) => self.write(cut, " <unfinished ...>\n"),
+            None => Ok(()),
+        }
+    }
+
+    /// Write `text` to where the trace of `pid` goes, as soon as possible
+    /// even if it is not a whole line.
+    fn write(&mut self, pid: Pid, text: &str) -> io::Result<()> {
+        match &mut self.sink {
+            Sink::Discard => Ok(()),
+            Sink::Stdout => {
+                let mut stdout = io::stdout();
+                stdout.write_all(text.as_bytes())?;
+                stdout.flush()
+            },
+            Sink::File(file) => {
+                file.write_all(text.as_bytes())?;
+                file.flush()
+            },
+            Sink::PerProcess { prefix, files, created } => {
+                let file = match files.entry(pid) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => {
+                        let mut name = prefix.clone().into_os_string();
+                        name.push(format!(".{}", pid));
+                        let file = if created.insert(pid) {
+                            File::create(name)?
+                        } else {
+                            OpenOptions::new().append(true).open(name)?
+                        };
+                        e.insert(LineWriter::new(file))
+                    },
+                };
+                file.write_all(text.as_bytes())?;
+                file.flush()
+            },
+        }
+    }
+}
+
+#[test]
+fn unfinished_test() {
+    use std::fs;
+    // Above the largest pid, so these are never threads of anything.
+    let (a, b) = (Pid::from_raw(5000001), Pid::from_raw(5000002));
+    let dir = std::env::temp_dir().join(format!("stracer-output-{}", nix::unistd::getpid()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("shared");
+    let mut output = Output::new(Some(path.clone()), false).unwrap();
+    output.start_line(a, "read(0, ").unwrap();
+    output.start_line(b, "getppid(").unwrap();
+    assert!(output.finish_line(b, ") = 1").unwrap());
+    assert!(!output.finish_line(a, "\"x\", 1) = 1").unwrap());
+    output.write_line(a, "<... read resumed>\"x\", 1) = 1").unwrap();
+    output.start_line(a, "pause(").unwrap();
+    output.interrupt_all().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "\
+[5000001]: read(0,  <unfinished ...>
+[5000002]: getppid() = 1
+[5000001]: <... read resumed>\"x\", 1) = 1
+[5000001]: pause( <unfinished ...>
+");
+
+    // With a file per process only a process's own lines cut its line short.
+    let prefix = dir.join("trace");
+    let mut output = Output::new(Some(prefix.clone()), true).unwrap();
+    output.start_line(a, "read(0, ").unwrap();
+    output.write_line(b, "getpid() = 5000002").unwrap();
+    assert!(output.finish_line(a, "\"x\", 1) = 1").unwrap());
+    output.start_line(b, "pause(").unwrap();
+    output.write_line(b, "--- SIGINT ---").unwrap();
+    output.start_line(a, "pause(").unwrap();
+    output.interrupt_all().unwrap();
+    let file = |pid: Pid| fs::read_to_string(format!("{}.{}", prefix.display(), pid)).unwrap();
+    assert_eq!(file(a), "read(0, \"x\", 1) = 1\npause( <unfinished ...>\n");
+    assert_eq!(file(b), "getpid() = 5000002\npause( <unfinished ...>\n--- SIGINT ---\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn forget_test() {
+    use std::fs;
+    let a = Pid::from_raw(5000003);
+    let dir = std::env::temp_dir().join(format!("stracer-forget-{}", nix::unistd::getpid()));
+    fs::create_dir_all(&dir).unwrap();
+    let prefix = dir.join("trace");
+    let mut output = Output::new(Some(prefix.clone()), true).unwrap();
+    let files = |output: &Output| match &output.sink {
+        Sink::PerProcess { files, .. } => files.len(),
+        _ => unreachable!(),
+    };
+    output.write_line(a, "exit_group(0) = ?").unwrap();
+    assert_eq!(files(&output), 1);
+    // Closed once the process is gone, and added to by the next one with
+    // its pid.
+    output.forget(a);
+    assert_eq!(files(&output), 0);
+    output.write_line(a, "getpid() = 5000003").unwrap();
+    output.forget(a);
+    let file = fs::read_to_string(format!("{}.{}", prefix.display(), a)).unwrap();
+    assert_eq!(file, "exit_group(0) = ?\ngetpid() = 5000003\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
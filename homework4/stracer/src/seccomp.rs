@@ -0,0 +1,96 @@
+//! Stopping the tracee only for the calls we report, with a seccomp-BPF
+//! filter that has the kernel hand just those to us, like strace's
+//! `--seccomp-bpf`. All other calls run at full speed: no ptrace stop at
+//! entry or exit.
+//!
+//! The filter is installed in the child before it executes the traced
+//! program, and inherited by everything it spawns. A task running under it
+//! without a tracer gets `ENOSYS` from the selected calls, so it is only used
+//! with processes we start and whose children we follow.
+
+use crate::arch::Arch;
+use libc::{c_void, sock_filter, sock_fprog};
+use nix::errno::Errno;
+
+/// Offsets of the fields of `struct seccomp_data`.
+const NR_OFFSET: u32 = 0;
+const ARCH_OFFSET: u32 = 4;
+
+fn statement(code: u32, k: u32) -> sock_filter {
+    sock_filter { code: code as u16, jt: 0, jf: 0, k }
+}
+
+/// Go on with the next instruction if the accumulator is `k`, otherwise skip
+/// it.
+fn if_equal(k: u32) -> sock_filter {
+    sock_filter { code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16, jt: 0, jf: 1, k }
+}
+
+fn load(offset: u32) -> sock_filter {
+    statement(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset)
+}
+
+fn ret(action: u32) -> sock_filter {
+    statement(libc::BPF_RET | libc::BPF_K, action)
+}
+
+/// A filter stopping the tracee for the calls `selected` picks by name, on
+/// every architecture it may run, and letting all others through.
+pub fn filter<F: Fn(&str) -> bool>(selected: F) -> Vec<sock_filter> {
+    // Each architecture's checks are jumped to from a dispatch on the
+    // architecture, the checks themselves only ever skip one instruction.
+    let blocks = Arch::all().iter()
+        .map(|&arch| {
+            let mut block = vec![load(NR_OFFSET)];
+            for (nr, _) in arch.syscalls().filter(|&(_, name)| selected(name)) {
+                block.push(if_equal(nr as u32));
+                block.push(ret(libc::SECCOMP_RET_TRACE));
+            }
+            block.push(ret(libc::SECCOMP_RET_ALLOW));
+            (arch, block)
+        })
+        .collect::<Vec<_>>();
+    let mut program = vec![load(ARCH_OFFSET)];
+    // The load, a check and a jump per architecture, and the return for
+    // unknown architectures come before the blocks.
+    let mut block_at = 2 * blocks.len() + 2;
+    for (i, (arch, block)) in blocks.iter().enumerate() {
+        program.push(if_equal(arch.audit_arch()));
+        // Relative to the instruction after this jump.
+        let next = 2 * i + 3;
+        program.push(statement(libc::BPF_JMP | libc::BPF_JA, (block_at - next) as u32));
+        block_at += block.len();
+    }
+    program.push(ret(libc::SECCOMP_RET_ALLOW));
+    for (_, block) in blocks {
+        program.extend(block);
+    }
+    program
+}
+
+/// Put the calling process under `filter`, for good.
+pub fn install(filter: &[sock_filter]) -> nix::Result<()> {
+    let program = sock_fprog { len: filter.len() as u16, filter: filter.as_ptr() as *mut sock_filter };
+    unsafe {
+        // Unprivileged processes may only install filters if they can't
+        // gain privileges afterwards.
+        Errno::result(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0))?;
+        Errno::result(libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER,
+                                  &program as *const sock_fprog as *const c_void))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn filter_test() {
+    let program = filter(|name| name == "openat");
+    let native = Arch::native();
+    let (nr, _) = native.syscalls().find(|&(_, name)| name == "openat").unwrap();
+    // The native architecture comes first, its jump lands on its block.
+    assert_eq!(program[1].k, native.audit_arch());
+    let block = 2 + program[2].k as usize + 1;
+    assert_eq!(program[block].k, NR_OFFSET);
+    assert_eq!(program[block + 1].k, nr as u32);
+    assert_eq!(program[block + 2].k, libc::SECCOMP_RET_TRACE);
+    assert_eq!(program[block + 3].k, libc::SECCOMP_RET_ALLOW);
+}
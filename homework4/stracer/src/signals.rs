@@ -0,0 +1,71 @@
+//! Signals delivered to tracees, printed the way strace does:
+//! `--- SIGCHLD {si_signo=SIGCHLD, si_code=CLD_EXITED, si_pid=42, ...} ---`.
+
+use crate::flags;
+use libc::siginfo_t;
+
+const SI_CODES: &[(u64, &str)] = &[
+    (0, "SI_USER"), (0x80, "SI_KERNEL"), (-1i64 as u64, "SI_QUEUE"), (-2i64 as u64, "SI_TIMER"),
+    (-3i64 as u64, "SI_MESGQ"), (-4i64 as u64, "SI_ASYNCIO"), (-5i64 as u64, "SI_SIGIO"),
+    (-6i64 as u64, "SI_TKILL"),
+];
+const CLD_CODES: &[(u64, &str)] = &[
+    (1, "CLD_EXITED"), (2, "CLD_KILLED"), (3, "CLD_DUMPED"), (4, "CLD_TRAPPED"),
+    (5, "CLD_STOPPED"), (6, "CLD_CONTINUED"),
+];
+const SEGV_CODES: &[(u64, &str)] = &[(1, "SEGV_MAPERR"), (2, "SEGV_ACCERR")];
+const BUS_CODES: &[(u64, &str)] = &[(1, "BUS_ADRALN"), (2, "BUS_ADRERR"), (3, "BUS_OBJERR")];
+
+/// The signals which stop a process, and so cause a group-stop when they are
+/// delivered to a tracee.
+pub fn is_stopping(sig: i32) -> bool {
+    [libc::SIGSTOP, libc::SIGTSTP, libc::SIGTTIN, libc::SIGTTOU].contains(&sig)
+}
+
+fn code_name(signo: i32, code: i32) -> String {
+    let names = match signo {
+        // Codes of zero and below say who sent the signal, whatever it is.
+        _ if code <= 0 || code == 0x80 => SI_CODES,
+        libc::SIGCHLD => CLD_CODES,
+        libc::SIGSEGV => SEGV_CODES,
+        libc::SIGBUS => BUS_CODES,
+        _ => return code.to_string(),
+    };
+    flags::constant(code as i64 as u64, names)
+}
+
+/// The fields of `info` which make sense for its signal and code.
+pub fn format_siginfo(info: &siginfo_t) -> String {
+    let mut fields = vec![
+        format!("si_signo={}", flags::signal_name(info.si_signo)),
+        format!("si_code={}", code_name(info.si_signo, info.si_code)),
+    ];
+    unsafe {
+        match info.si_signo {
+            libc::SIGCHLD if info.si_code > 0 => {
+                fields.push(format!("si_pid={}", info.si_pid()));
+                fields.push(format!("si_uid={}", info.si_uid()));
+                fields.push(format!("si_status={}", info.si_status()));
+            },
+            libc::SIGSEGV | libc::SIGBUS | libc::SIGILL | libc::SIGFPE if info.si_code > 0 => {
+                fields.push(format!("si_addr={:#x}", info.si_addr() as usize));
+            },
+            _ if info.si_code <= 0 => {
+                fields.push(format!("si_pid={}", info.si_pid()));
+                fields.push(format!("si_uid={}", info.si_uid()));
+            },
+            _ => (),
+        }
+    }
+    format!("{{{}}}", fields.join(", "))
+}
+
+#[test]
+fn format_siginfo_test() {
+    let mut info: siginfo_t = unsafe { std::mem::zeroed() };
+    info.si_signo = libc::SIGINT;
+    info.si_code = 0x80;
+    assert_eq!(format_siginfo(&info), "{si_signo=SIGINT, si_code=SI_KERNEL}");
+    info.si_code = 0;
+    assert_eq!(format_siginfo(&info), "{si_signo=SIGINT, si_code=SI_USER, si_pid=0, si_uid=0}");
+}
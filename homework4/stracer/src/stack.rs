@@ -0,0 +1,227 @@
+//! The user space call stack of a tracee stopped at a system call, as `-k`
+//! prints it under each call. Frames are unwound with the `.eh_frame` tables
+//! of the binaries the tracee has mapped, falling back to frame pointers for
+//! code without them, and named after the ELF symbols they are in.
+
+use crate::arch::{Arch, Regs};
+use crate::dwarf::{EhFrame, Rule, Rules, REGISTERS};
+use crate::elf::Elf;
+use crate::json;
+use crate::memory;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::fs;
+
+/// Deeper stacks are cut off, they are most likely unwound wrong anyway.
+const MAX_FRAMES: usize = 64;
+
+/// The DWARF numbers of the stack and frame pointer.
+#[cfg(target_arch = "x86_64")]
+const SP: usize = 7;
+#[cfg(target_arch = "x86_64")]
+const FP: usize = 6;
+#[cfg(target_arch = "aarch64")]
+const SP: usize = 31;
+#[cfg(target_arch = "aarch64")]
+const FP: usize = 29;
+
+/// One return address on the stack, or where the tracee is stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub address: u64,
+    /// The file mapped at `address`, if any.
+    pub path: Option<String>,
+    /// Where in the file `address` is.
+    pub offset: u64,
+    /// The function `address` is in, and how far into it.
+    pub symbol: Option<(String, u64)>,
+}
+
+impl Frame {
+    /// ` > path(symbol+0x1f) [0xoffset]`, as strace prints frames.
+    pub fn to_text(&self) -> String {
+        match (&self.path, &self.symbol) {
+            (Some(path), Some((name, offset))) => {
+                format!(" > {}({}+{:#x}) [{:#x}]", path, name, offset, self.offset)
+            },
+            (Some(path), None) => format!(" > {}() [{:#x}]", path, self.offset),
+            (None, _) => format!(" > ?? [{:#x}]", self.address),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        json::object(&[
+            ("address", self.address.to_string()),
+            ("path", json::optional(&self.path, |p| json::string(p))),
+            ("offset", self.offset.to_string()),
+            ("symbol", json::optional(&self.symbol, |(name, _)| json::string(name))),
+            ("symbol_offset", json::optional(&self.symbol, |(_, offset)| offset.to_string())),
+        ])
+    }
+}
+
+/// A line of `/proc/PID/maps`.
+struct Mapping {
+    start: u64,
+    end: u64,
+    offset: u64,
+    path: Option<String>,
+}
+
+fn mappings(pid: Pid) -> Vec<Mapping> {
+    let maps = fs::read_to_string(format!("/proc/{}/maps", pid)).unwrap_or_default();
+    maps.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(6, ' ');
+            let mut range = fields.next()?.split('-');
+            let start = u64::from_str_radix(range.next()?, 16).ok()?;
+            let end = u64::from_str_radix(range.next()?, 16).ok()?;
+            let offset = u64::from_str_radix(fields.nth(1)?, 16).ok()?;
+            let path = fields.nth(2).map(str::trim_start).filter(|path| path.starts_with('/'));
+            Some(Mapping { start, end, offset, path: path.map(str::to_string) })
+        })
+        .collect()
+}
+
+struct Binary {
+    elf: Elf,
+    eh_frame: Option<EhFrame>,
+}
+
+/// Unwinds stacks, keeping the binaries it read for the rest of the trace.
+#[derive(Default)]
+pub struct Unwinder {
+    /// By path, `None` for those that could not be read.
+    binaries: HashMap<String, Option<Binary>>,
+}
+
+fn read_word(pid: Pid, address: u64) -> Option<u64> {
+    let bytes = memory::read_bytes(pid, address, 8).ok()?;
+    Some(bytes.iter().rev().fold(0, |word, &b| (word << 8) | u64::from(b)))
+}
+
+impl Unwinder {
+    pub fn new() -> Unwinder {
+        Unwinder::default()
+    }
+
+    fn binary(&mut self, path: &str) -> Option<&Binary> {
+        self.binaries.entry(path.to_string())
+            .or_insert_with(|| {
+                let mut elf = Elf::read(path).ok()?;
+                let eh_frame = elf.eh_frame.take().map(|(addr, data)| EhFrame::new(addr, data));
+                Some(Binary { elf, eh_frame })
+            })
+            .as_ref()
+    }
+
+    /// The stack of thread `pid`, stopped with `regs`, innermost frame
+    /// first. Only tracees of the native architecture are unwound, others
+    /// get just the frame they are stopped in.
+    pub fn stack(&mut self, pid: Pid, regs: &Regs) -> Vec<Frame> {
+        let maps = mappings(pid);
+        let mut registers = [None; REGISTERS];
+        for (i, value) in regs.dwarf_registers().into_iter().enumerate() {
+            registers[i] = Some(value);
+        }
+        let mut pc = regs.pc();
+        let mut frames = Vec::new();
+        while pc != 0 && frames.len() < MAX_FRAMES {
+            // Return addresses are just past the call, which may be the
+            // last instruction of the function.
+            let at = if frames.is_empty() { pc } else { pc - 1 };
+            let mapping = match maps.iter().find(|m| at >= m.start && at < m.end) {
+                Some(mapping) => mapping,
+                None => break,
+            };
+            let offset = (at - mapping.start).wrapping_add(mapping.offset);
+            let binary = mapping.path.as_ref().and_then(|path| self.binary(path));
+            let vaddr = binary.and_then(|binary| binary.elf.vaddr(offset));
+            // Shown as the return address all the same.
+            let past = pc - at;
+            frames.push(Frame {
+                address: pc,
+                path: mapping.path.clone(),
+                offset: offset + past,
+                symbol: binary.zip(vaddr)
+                    .and_then(|(binary, vaddr)| binary.elf.symbol(vaddr))
+                    .map(|(name, offset)| (name.to_string(), offset + past)),
+            });
+            if regs.arch != Arch::native() {
+                break;
+            }
+            let rules = binary.and_then(|binary| binary.eh_frame.as_ref()).zip(vaddr)
+                .and_then(|(eh_frame, vaddr)| eh_frame.rules(vaddr));
+            let sp = registers[SP];
+            let next = match rules {
+                Some(rules) => unwind_cfi(pid, &registers, &rules),
+                None => unwind_fp(pid, &registers),
+            };
+            match next {
+                // The stack grows down, callers are above.
+                Some((next, return_address)) if next[SP] > sp => {
+                    registers = next;
+                    pc = return_address;
+                },
+                _ => break,
+            }
+        }
+        frames
+    }
+}
+
+/// The registers of the caller and where it continues, by the rules for
+/// the current frame.
+fn unwind_cfi(pid: Pid, registers: &[Option<u64>; REGISTERS], rules: &Rules)
+              -> Option<([Option<u64>; REGISTERS], u64)> {
+    let (cfa_register, cfa_offset) = rules.cfa;
+    let cfa = registers.get(cfa_register as usize).cloned()??.wrapping_add(cfa_offset as u64);
+    let mut next = *registers;
+    for (&register, &rule) in &rules.registers {
+        let value = match rule {
+            Rule::Undefined => None,
+            Rule::SameValue => continue,
+            Rule::Offset(offset) => read_word(pid, cfa.wrapping_add(offset as u64)),
+            Rule::ValOffset(offset) => Some(cfa.wrapping_add(offset as u64)),
+            Rule::Register(other) => registers.get(other as usize).cloned().flatten(),
+        };
+        if let Some(slot) = next.get_mut(register as usize) {
+            *slot = value;
+        }
+    }
+    let return_address = next.get(rules.return_address as usize).cloned()??;
+    next[SP] = Some(cfa);
+    Some((next, return_address))
+}
+
+/// The registers of the caller and where it continues, if the current frame
+/// keeps the caller's frame pointer and the return address next to each
+/// other where its frame pointer points.
+fn unwind_fp(pid: Pid, registers: &[Option<u64>; REGISTERS]) -> Option<([Option<u64>; REGISTERS], u64)> {
+    let fp = registers[FP]?;
+    let mut next = *registers;
+    next[FP] = Some(read_word(pid, fp)?);
+    next[SP] = Some(fp.wrapping_add(16));
+    Some((next, read_word(pid, fp.wrapping_add(8))?))
+}
+
+#[test]
+fn stack_test() {
+    use crate::events::Event;
+    use crate::tracer::Tracer;
+    let mut stack = None;
+    Tracer::new()
+        .filter("trace=exit_group").unwrap()
+        .stack_trace(true)
+        .spawn("/bin/sh", &["sh", "-c", "exit 3"], |event| {
+            if let Event::Syscall(call) = event {
+                stack = call.stack;
+            }
+        })
+        .unwrap();
+    // Stopped in libc, called from the shell's main.
+    let stack = stack.unwrap();
+    assert!(stack.len() > 1);
+    assert!(stack[0].path.as_ref().unwrap().contains("libc"));
+    assert!(stack.iter().any(|frame| frame.path.as_ref().is_some_and(|path| path.contains("sh"))));
+}
@@ -0,0 +1,239 @@
+//! Structures passed to system calls by pointer, read from the tracee and
+//! printed field by field, so `connect(3, {sa_family=AF_INET,
+//! sin_port=htons(80), sin_addr=inet_addr("127.0.0.1")}, 16)` is printed
+//! instead of `connect(3, 0x7ffd1c2e0f10, 16)`.
+
+use crate::arch::Arch;
+use crate::buffers;
+use crate::flags;
+use crate::memory;
+use libc::{pollfd, stat, timespec};
+use nix::unistd::Pid;
+use std::cmp::min;
+use std::mem;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Stat,
+    Timespec,
+    /// A socket address whose length is the given argument.
+    Sockaddr(usize),
+    /// A socket address filled in by the kernel, its length stored at the
+    /// address in the given argument.
+    SockaddrOut(usize),
+    /// An array of `pollfd`s as long as the given argument.
+    Pollfd(usize),
+    Sigaction,
+    Sigset,
+}
+
+/// The struct arguments of a call: the argument, what it points to, and
+/// whether the kernel fills it in, so it is only there at exit.
+fn struct_args(name: &str) -> &'static [(usize, Kind, bool)] {
+    use self::Kind::*;
+    match name {
+        "stat" | "fstat" | "lstat" => &[(1, Stat, true)],
+        "newfstatat" => &[(2, Stat, true)],
+        "nanosleep" => &[(0, Timespec, false)],
+        "clock_nanosleep" => &[(2, Timespec, false)],
+        "clock_gettime" | "clock_getres" => &[(1, Timespec, true)],
+        "clock_settime" => &[(1, Timespec, false)],
+        "poll" => &[(0, Pollfd(1), false)],
+        "ppoll" => &[(0, Pollfd(1), false), (2, Timespec, false)],
+        "connect" | "bind" => &[(1, Sockaddr(2), false)],
+        "sendto" => &[(4, Sockaddr(5), false)],
+        "accept" | "accept4" | "getsockname" | "getpeername" => &[(1, SockaddrOut(2), true)],
+        "recvfrom" => &[(4, SockaddrOut(5), true)],
+        "rt_sigaction" => &[(1, Sigaction, false), (2, Sigaction, true)],
+        "rt_sigprocmask" => &[(1, Sigset, false), (2, Sigset, true)],
+        _ => &[],
+    }
+}
+
+/// Most `pollfd`s printed for a call.
+const MAX_POLLFDS: u64 = 64;
+
+#[cfg(target_arch = "x86_64")]
+const SA_RESTORER: u64 = 0x04000000;
+
+/// The kernel's `struct sigaction`, which is laid out unlike libc's. Only
+/// x86_64 has the `sa_restorer` slot, aarch64 returns from handlers through
+/// the vDSO.
+#[cfg(target_arch = "x86_64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct KernelSigaction {
+    handler: u64,
+    flags: u64,
+    restorer: u64,
+    mask: u64,
+}
+#[cfg(target_arch = "aarch64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct KernelSigaction {
+    handler: u64,
+    flags: u64,
+    mask: u64,
+}
+
+/// The first argument of `name` which the kernel fills in, if any.
+pub fn filled_at_exit(name: &str) -> Option<usize> {
+    struct_args(name).iter().filter(|&&(_, _, out)| out).map(|&(idx, _, _)| idx).min()
+}
+
+/// The struct arguments of `name` which are there at entry, or at exit, each
+/// with its index. Arguments which are NULL or cannot be read are left out.
+/// The structs are read with our own layouts, so tracees of other ABIs, such
+/// as i386 with its 32-bit `stat` and `timespec`, get none.
+pub fn decode(pid: Pid, arch: Arch, name: &str, args: &[u64], at_exit: bool)
+              -> Vec<(usize, String)> {
+    if arch != Arch::native() {
+        return Vec::new();
+    }
+    struct_args(name).iter()
+        .filter(|&&(idx, _, out)| out == at_exit && args[idx] != 0)
+        .filter_map(|&(idx, kind, _)| read(pid, kind, args[idx], args).ok().map(|s| (idx, s)))
+        .collect()
+}
+
+fn read(pid: Pid, kind: Kind, address: u64, args: &[u64]) -> nix::Result<String> {
+    Ok(match kind {
+        Kind::Stat => format_stat(&memory::read_struct::<stat>(pid, address)?),
+        Kind::Timespec => format_timespec(&memory::read_struct::<timespec>(pid, address)?),
+        Kind::Sockaddr(len) => {
+            format_sockaddr(&memory::read_bytes(pid, address, min(args[len], 128) as usize)?)
+        },
+        Kind::SockaddrOut(len) => {
+            let len: u32 = memory::read_struct(pid, args[len])?;
+            format_sockaddr(&memory::read_bytes(pid, address, min(len, 128) as usize)?)
+        },
+        Kind::Pollfd(count) => {
+            let fds = (0..min(args[count], MAX_POLLFDS))
+                .map(|i| address.wrapping_add(i * mem::size_of::<pollfd>() as u64))
+                .map(|at| memory::read_struct::<pollfd>(pid, at).map(|fd| format_pollfd(&fd)))
+                .collect::<nix::Result<Vec<String>>>()?;
+            let more = if args[count] > MAX_POLLFDS { ", ..." } else { "" };
+            format!("[{}{}]", fds.join(", "), more)
+        },
+        Kind::Sigaction => format_sigaction(&memory::read_struct(pid, address)?),
+        Kind::Sigset => format_sigset(memory::read_struct(pid, address)?),
+    })
+}
+
+fn format_stat(st: &stat) -> String {
+    let dev = st.st_dev;
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    format!("{{st_dev=makedev({:#x}, {:#x}), st_ino={}, st_mode={}, st_nlink={}, st_uid={}, \
+             st_gid={}, st_size={}, ...}}",
+            major, minor, st.st_ino, flags::file_mode(st.st_mode as u64), st.st_nlink,
+            st.st_uid, st.st_gid, st.st_size)
+}
+
+fn format_timespec(ts: &timespec) -> String {
+    format!("{{tv_sec={}, tv_nsec={}}}", ts.tv_sec, ts.tv_nsec)
+}
+
+fn format_pollfd(fd: &pollfd) -> String {
+    format!("{{fd={}, events={}}}", fd.fd, flags::poll_events(fd.events as u16 as u64))
+}
+
+/// A `sockaddr` of any family, from the `bytes` of its length.
+pub fn format_sockaddr(bytes: &[u8]) -> String {
+    if bytes.len() < 2 {
+        return "{}".to_string();
+    }
+    let family = u16::from_ne_bytes([bytes[0], bytes[1]]);
+    let data = &bytes[2..];
+    let port = |b: &[u8]| u16::from_be_bytes([b[0], b[1]]);
+    let fields = match family as i32 {
+        libc::AF_UNIX if data.first() == Some(&0) => {
+            // Abstract socket names start with a NUL and are not terminated.
+            format!("sun_path=@{}", buffers::escape(&data[1..], false))
+        },
+        libc::AF_UNIX => {
+            let path = data.split(|&b| b == 0).next().unwrap_or(&[]);
+            format!("sun_path={}", buffers::escape(path, false))
+        },
+        libc::AF_INET if data.len() >= 6 => {
+            let addr = Ipv4Addr::new(data[2], data[3], data[4], data[5]);
+            format!("sin_port=htons({}), sin_addr=inet_addr(\"{}\")", port(data), addr)
+        },
+        libc::AF_INET6 if data.len() >= 26 => {
+            let mut addr = [0; 16];
+            addr.copy_from_slice(&data[6..22]);
+            let flowinfo = u32::from_be_bytes([data[2], data[3], data[4], data[5]]);
+            let scope_id = u32::from_ne_bytes([data[22], data[23], data[24], data[25]]);
+            format!("sin6_port=htons({}), sin6_flowinfo=htonl({}), \
+                     inet_pton(AF_INET6, \"{}\", &sin6_addr), sin6_scope_id={}",
+                    port(data), flowinfo, Ipv6Addr::from(addr), scope_id)
+        },
+        _ => format!("sa_data={}", buffers::escape(data, false)),
+    };
+    format!("{{sa_family={}, {}}}", flags::address_family(family as u64), fields)
+}
+
+fn format_sigaction(act: &KernelSigaction) -> String {
+    let handler = match act.handler {
+        0 => "SIG_DFL".to_string(),
+        1 => "SIG_IGN".to_string(),
+        h => format!("{:#x}", h),
+    };
+    let mut s = format!("{{sa_handler={}, sa_mask={}, sa_flags={}",
+                        handler, format_sigset(act.mask), flags::sigaction_flags(act.flags));
+    #[cfg(target_arch = "x86_64")]
+    {
+        if act.flags & SA_RESTORER != 0 {
+            s.push_str(&format!(", sa_restorer={:#x}", act.restorer));
+        }
+    }
+    s.push('}');
+    s
+}
+
+/// A signal set as the names of its signals, or as the signals it leaves
+/// out when that is shorter.
+pub fn format_sigset(set: u64) -> String {
+    let (prefix, set) = if set.count_ones() > 32 { ("~", !set) } else { ("", set) };
+    let names = (1..=64)
+        .filter(|sig| set & (1 << (sig - 1)) != 0)
+        .map(|sig| {
+            let name = flags::signal_name(sig);
+            name.trim_start_matches("SIG").to_string()
+        })
+        .collect::<Vec<String>>();
+    format!("{}[{}]", prefix, names.join(" "))
+}
+
+#[test]
+fn sockaddr_test() {
+    let inet = [2, 0, 0, 80, 127, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+    assert_eq!(format_sockaddr(&inet),
+               "{sa_family=AF_INET, sin_port=htons(80), sin_addr=inet_addr(\"127.0.0.1\")}");
+    let unix = b"\x01\x00/run/sock\0";
+    assert_eq!(format_sockaddr(unix), "{sa_family=AF_UNIX, sun_path=\"/run/sock\"}");
+}
+
+#[test]
+fn sigset_test() {
+    assert_eq!(format_sigset(0), "[]");
+    assert_eq!(format_sigset((1 << 1) | (1 << 14)), "[INT TERM]");
+    assert_eq!(format_sigset(!(1 << 8)), "~[KILL]");
+}
+
+#[test]
+fn decode_test() {
+    let ts = timespec { tv_sec: 5, tv_nsec: 7 };
+    let pid = nix::unistd::getpid();
+    let args = [&ts as *const timespec as u64];
+    assert_eq!(decode(pid, Arch::native(), "nanosleep", &args, false),
+               vec![(0, "{tv_sec=5, tv_nsec=7}".to_string())]);
+    // An i386 timespec is two 32-bit fields, ours would read it wrong.
+    #[cfg(target_arch = "x86_64")]
+    assert!(decode(pid, Arch::I386, "nanosleep", &args, false).is_empty());
+    // The count is up to the tracee, addresses past the end wrap around
+    // and fail to read rather than overflow.
+    assert!(decode(pid, Arch::native(), "poll", &[u64::MAX, 2], false).is_empty());
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Totals for one system call.
+#[derive(Debug, Default)]
+struct Stat {
+    calls: u64,
+    errors: u64,
+    time: Duration,
+}
+
+/// Per system call counts, error counts and time spent, printed as a table
+/// when the trace is over, like `strace -c`.
+#[derive(Debug, Default)]
+pub struct Summary {
+    stats: HashMap<&'static str, Stat>,
+}
+
+impl Summary {
+    pub fn new() -> Summary {
+        Summary::default()
+    }
+
+    pub fn record(&mut self, name: &'static str, time: Duration, failed: bool) {
+        let stat = self.stats.entry(name).or_default();
+        stat.calls += 1;
+        stat.time += time;
+        if failed {
+            stat.errors += 1;
+        }
+    }
+
+    /// The table, busiest system call first.
+    pub fn report(&self) -> String {
+        let mut rows = self.stats.iter().collect::<Vec<_>>();
+        rows.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(b.1.calls.cmp(&a.1.calls)));
+        let total_time = self.stats.values().map(|s| s.time).sum::<Duration>();
+        let total_calls = self.stats.values().map(|s| s.calls).sum::<u64>();
+        let total_errors = self.stats.values().map(|s| s.errors).sum::<u64>();
+        let separator = format!("{:-<6} {:-<11} {:-<11} {:-<9} {:-<9} {:-<16}\n",
+                                "", "", "", "", "", "");
+
+        let mut out = format!("{:>6} {:>11} {:>11} {:>9} {:>9} {}\n",
+                              "% time", "seconds", "usecs/call", "calls", "errors", "syscall");
+        out.push_str(&separator);
+        for (name, stat) in rows {
+            let percent = if total_time.as_nanos() > 0 {
+                100.0 * stat.time.as_secs_f64() / total_time.as_secs_f64()
+            } else {
+                0.0
+            };
+            let usecs = stat.time.as_micros() / u128::from(stat.calls);
+            out.push_str(&format!("{:>6.2} {:>11.6} {:>11} {:>9} {:>9} {}\n",
+                                  percent, stat.time.as_secs_f64(), usecs, stat.calls,
+                                  errors_column(stat.errors), name));
+        }
+        out.push_str(&separator);
+        out.push_str(&format!("{:>6.2} {:>11.6} {:>11} {:>9} {:>9} {}\n",
+                              100.0, total_time.as_secs_f64(), "", total_calls,
+                              errors_column(total_errors), "total"));
+        out
+    }
+}
+
+/// Like strace we leave the column empty rather than print a zero.
+fn errors_column(errors: u64) -> String {
+    if errors == 0 { String::new() } else { errors.to_string() }
+}
+
+#[test]
+fn report_test() {
+    let mut summary = Summary::new();
+    summary.record("read", Duration::from_micros(30), false);
+    summary.record("read", Duration::from_micros(10), false);
+    summary.record("openat", Duration::from_micros(60), true);
+    let report = summary.report();
+    let lines = report.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[2], " 60.00    0.000060          60         1         1 openat");
+    assert_eq!(lines[3], " 40.00    0.000040          20         2           read");
+    assert_eq!(lines[5], "100.00    0.000100                     3         1 total");
+}
@@ -0,0 +1,1457 @@
+/// What a system call argument holds, which decides how it is read out of the
+/// tracee and printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    /// A C `int`, only the low 32 bits of the register are meaningful.
+    Int,
+    /// A full width integer such as `size_t`, `off_t` or `long`.
+    Long,
+    /// A file descriptor.
+    Fd,
+    /// An address we don't look behind.
+    Ptr,
+    /// A pointer to a NUL terminated string.
+    Str,
+    /// A bitmask or a constant picked from a set of values.
+    Flags,
+    /// A pointer to a structure.
+    Struct,
+    /// A NULL terminated array of pointers to strings, such as `argv`.
+    StrArray,
+    /// An environment, a string array counted rather than printed.
+    Env,
+}
+
+/// What a system call returns when it succeeds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetKind {
+    /// A count, a descriptor, a process id or the like.
+    Int,
+    /// An address in the caller's memory.
+    Address,
+}
+
+/// The calls returning addresses, the same on every architecture. All
+/// others return `RetKind::Int`.
+static ADDRESS_RETURNS: [&str; 5] = ["mmap", "mmap2", "mremap", "brk", "shmat"];
+
+/// What call `name` returns.
+pub fn ret_kind(name: &str) -> RetKind {
+    if ADDRESS_RETURNS.contains(&name) { RetKind::Address } else { RetKind::Int }
+}
+
+use self::ArgKind::*;
+
+/// The arguments each system call takes, indexed like `SYSTEM_CALL_NAMES`.
+#[cfg(target_arch = "x86_64")]
+pub static SYSTEM_CALL_ARGS: [&[ArgKind]; 467] = [
+    &[Fd, Ptr, Long], // read
+    &[Fd, Ptr, Long], // write
+    &[Str, Flags, Flags], // open
+    &[Fd], // close
+    &[Str, Struct], // stat
+    &[Fd, Struct], // fstat
+    &[Str, Struct], // lstat
+    &[Struct, Long, Int], // poll
+    &[Fd, Long, Flags], // lseek
+    &[Ptr, Long, Flags, Flags, Fd, Long], // mmap
+    &[Ptr, Long, Flags], // mprotect
+    &[Ptr, Long], // munmap
+    &[Ptr], // brk
+    &[Int, Struct, Struct, Long], // rt_sigaction
+    &[Flags, Struct, Struct, Long], // rt_sigprocmask
+    &[], // rt_sigreturn
+    &[Fd, Flags, Ptr], // ioctl
+    &[Fd, Ptr, Long, Long], // pread64
+    &[Fd, Ptr, Long, Long], // pwrite64
+    &[Fd, Struct, Long], // readv
+    &[Fd, Struct, Long], // writev
+    &[Str, Flags], // access
+    &[Ptr], // pipe
+    &[Int, Ptr, Ptr, Ptr, Struct], // select
+    &[], // sched_yield
+    &[Ptr, Long, Long, Flags, Ptr], // mremap
+    &[Ptr, Long, Flags], // msync
+    &[Ptr, Long, Ptr], // mincore
+    &[Ptr, Long, Flags], // madvise
+    &[Int, Long, Flags], // shmget
+    &[Int, Ptr, Flags], // shmat
+    &[Int, Flags, Ptr], // shmctl
+    &[Fd], // dup
+    &[Fd, Fd], // dup2
+    &[], // pause
+    &[Struct, Struct], // nanosleep
+    &[Int, Struct], // getitimer
+    &[Int], // alarm
+    &[Int, Struct, Struct], // setitimer
+    &[], // getpid
+    &[Fd, Fd, Ptr, Long], // sendfile
+    &[Flags, Flags, Int], // socket
+    &[Fd, Struct, Long], // connect
+    &[Fd, Ptr, Ptr], // accept
+    &[Fd, Ptr, Long, Flags, Struct, Long], // sendto
+    &[Fd, Ptr, Long, Flags, Ptr, Ptr], // recvfrom
+    &[Fd, Struct, Flags], // sendmsg
+    &[Fd, Struct, Flags], // recvmsg
+    &[Fd, Flags], // shutdown
+    &[Fd, Struct, Long], // bind
+    &[Fd, Int], // listen
+    &[Fd, Ptr, Ptr], // getsockname
+    &[Fd, Ptr, Ptr], // getpeername
+    &[Flags, Flags, Int, Ptr], // socketpair
+    &[Fd, Flags, Flags, Ptr, Long], // setsockopt
+    &[Fd, Flags, Flags, Ptr, Ptr], // getsockopt
+    &[Flags, Ptr, Ptr, Ptr, Long], // clone
+    &[], // fork
+    &[], // vfork
+    &[Str, StrArray, Env], // execve
+    &[Int], // exit
+    &[Int, Ptr, Flags, Ptr], // wait4
+    &[Int, Int], // kill
+    &[Ptr], // uname
+    &[Int, Int, Flags], // semget
+    &[Int, Ptr, Long], // semop
+    &[Int, Int, Flags, Ptr], // semctl
+    &[Ptr], // shmdt
+    &[Int, Flags], // msgget
+    &[Int, Ptr, Long, Flags], // msgsnd
+    &[Int, Ptr, Long, Long, Flags], // msgrcv
+    &[Int, Flags, Ptr], // msgctl
+    &[Fd, Flags, Long], // fcntl
+    &[Fd, Flags], // flock
+    &[Fd], // fsync
+    &[Fd], // fdatasync
+    &[Str, Long], // truncate
+    &[Fd, Long], // ftruncate
+    &[Fd, Ptr, Long], // getdents
+    &[Ptr, Long], // getcwd
+    &[Str], // chdir
+    &[Fd], // fchdir
+    &[Str, Str], // rename
+    &[Str, Flags], // mkdir
+    &[Str], // rmdir
+    &[Str, Flags], // creat
+    &[Str, Str], // link
+    &[Str], // unlink
+    &[Str, Str], // symlink
+    &[Str, Ptr, Long], // readlink
+    &[Str, Flags], // chmod
+    &[Fd, Flags], // fchmod
+    &[Str, Int, Int], // chown
+    &[Fd, Int, Int], // fchown
+    &[Str, Int, Int], // lchown
+    &[Flags], // umask
+    &[Ptr, Ptr], // gettimeofday
+    &[Flags, Ptr], // getrlimit
+    &[Int, Ptr], // getrusage
+    &[Ptr], // sysinfo
+    &[Ptr], // times
+    &[Flags, Int, Ptr, Ptr], // ptrace
+    &[], // getuid
+    &[Int, Ptr, Int], // syslog
+    &[], // getgid
+    &[Int], // setuid
+    &[Int], // setgid
+    &[], // geteuid
+    &[], // getegid
+    &[Int, Int], // setpgid
+    &[], // getppid
+    &[], // getpgrp
+    &[], // setsid
+    &[Int, Int], // setreuid
+    &[Int, Int], // setregid
+    &[Int, Ptr], // getgroups
+    &[Int, Ptr], // setgroups
+    &[Int, Int, Int], // setresuid
+    &[Ptr, Ptr, Ptr], // getresuid
+    &[Int, Int, Int], // setresgid
+    &[Ptr, Ptr, Ptr], // getresgid
+    &[Int], // getpgid
+    &[Int], // setfsuid
+    &[Int], // setfsgid
+    &[Int], // getsid
+    &[Ptr, Ptr], // capget
+    &[Ptr, Ptr], // capset
+    &[Ptr, Long], // rt_sigpending
+    &[Ptr, Ptr, Struct, Long], // rt_sigtimedwait
+    &[Int, Int, Ptr], // rt_sigqueueinfo
+    &[Ptr, Long], // rt_sigsuspend
+    &[Ptr, Ptr], // sigaltstack
+    &[Str, Ptr], // utime
+    &[Str, Flags, Long], // mknod
+    &[Str], // uselib
+    &[Flags], // personality
+    &[Long, Ptr], // ustat
+    &[Str, Ptr], // statfs
+    &[Fd, Ptr], // fstatfs
+    &[Int, Long, Long], // sysfs
+    &[Int, Int], // getpriority
+    &[Int, Int, Int], // setpriority
+    &[Int, Ptr], // sched_setparam
+    &[Int, Ptr], // sched_getparam
+    &[Int, Flags, Ptr], // sched_setscheduler
+    &[Int], // sched_getscheduler
+    &[Flags], // sched_get_priority_max
+    &[Flags], // sched_get_priority_min
+    &[Int, Struct], // sched_rr_get_interval
+    &[Ptr, Long], // mlock
+    &[Ptr, Long], // munlock
+    &[Flags], // mlockall
+    &[], // munlockall
+    &[], // vhangup
+    &[Int, Ptr, Long], // modify_ldt
+    &[Str, Str], // pivot_root
+    &[Ptr], // _sysctl
+    &[Flags, Long, Long, Long, Long], // prctl
+    &[Flags, Long], // arch_prctl
+    &[Ptr], // adjtimex
+    &[Flags, Ptr], // setrlimit
+    &[Str], // chroot
+    &[], // sync
+    &[Str], // acct
+    &[Ptr, Ptr], // settimeofday
+    &[Str, Str, Str, Flags, Ptr], // mount
+    &[Str, Flags], // umount2
+    &[Str, Flags], // swapon
+    &[Str], // swapoff
+    &[Flags, Flags, Flags, Ptr], // reboot
+    &[Ptr, Long], // sethostname
+    &[Ptr, Long], // setdomainname
+    &[Int], // iopl
+    &[Long, Long, Int], // ioperm
+    &[Str, Long], // create_module
+    &[Ptr, Long, Str], // init_module
+    &[Str, Flags], // delete_module
+    &[Ptr], // get_kernel_syms
+    &[Str, Flags, Ptr, Long, Ptr], // query_module
+    &[Flags, Str, Int, Ptr], // quotactl
+    &[Int, Ptr, Ptr], // nfsservctl
+    &[], // getpmsg
+    &[], // putpmsg
+    &[], // afs_syscall
+    &[], // tuxcall
+    &[], // security
+    &[], // gettid
+    &[Fd, Long, Long], // readahead
+    &[Str, Str, Ptr, Long, Long], // setxattr
+    &[Str, Str, Ptr, Long, Long], // lsetxattr
+    &[Fd, Str, Ptr, Long, Long], // fsetxattr
+    &[Str, Str, Ptr, Long], // getxattr
+    &[Str, Str, Ptr, Long], // lgetxattr
+    &[Fd, Str, Ptr, Long], // fgetxattr
+    &[Str, Ptr, Long], // listxattr
+    &[Str, Ptr, Long], // llistxattr
+    &[Fd, Ptr, Long], // flistxattr
+    &[Str, Str], // removexattr
+    &[Str, Str], // lremovexattr
+    &[Fd, Str], // fremovexattr
+    &[Int, Int], // tkill
+    &[Ptr], // time
+    &[Ptr, Flags, Int, Struct, Ptr, Int], // futex
+    &[Int, Long, Ptr], // sched_setaffinity
+    &[Int, Long, Ptr], // sched_getaffinity
+    &[Ptr], // set_thread_area
+    &[Int, Ptr], // io_setup
+    &[Long], // io_destroy
+    &[Long, Long, Long, Ptr, Struct], // io_getevents
+    &[Long, Long, Ptr], // io_submit
+    &[Long, Ptr, Ptr], // io_cancel
+    &[Ptr], // get_thread_area
+    &[Long, Ptr, Long], // lookup_dcookie
+    &[Int], // epoll_create
+    &[], // epoll_ctl_old
+    &[], // epoll_wait_old
+    &[Ptr, Long, Long, Long, Long], // remap_file_pages
+    &[Fd, Ptr, Long], // getdents64
+    &[Ptr], // set_tid_address
+    &[], // restart_syscall
+    &[Int, Ptr, Long, Struct], // semtimedop
+    &[Fd, Long, Long, Flags], // fadvise64
+    &[Flags, Ptr, Ptr], // timer_create
+    &[Int, Flags, Struct, Ptr], // timer_settime
+    &[Int, Ptr], // timer_gettime
+    &[Int], // timer_getoverrun
+    &[Int], // timer_delete
+    &[Flags, Struct], // clock_settime
+    &[Flags, Ptr], // clock_gettime
+    &[Flags, Ptr], // clock_getres
+    &[Flags, Flags, Struct, Ptr], // clock_nanosleep
+    &[Int], // exit_group
+    &[Fd, Ptr, Int, Int], // epoll_wait
+    &[Fd, Flags, Fd, Ptr], // epoll_ctl
+    &[Int, Int, Int], // tgkill
+    &[Str, Ptr], // utimes
+    &[], // vserver
+    &[Ptr, Long, Flags, Ptr, Long], // mbind
+    &[Flags, Ptr, Long], // set_mempolicy
+    &[Ptr, Ptr, Long, Long, Flags], // get_mempolicy
+    &[Str, Flags, Flags, Ptr], // mq_open
+    &[Str], // mq_unlink
+    &[Fd, Ptr, Long, Int, Struct], // mq_timedsend
+    &[Fd, Ptr, Long, Ptr, Struct], // mq_timedreceive
+    &[Fd, Ptr], // mq_notify
+    &[Fd, Ptr, Ptr], // mq_getsetattr
+    &[Long, Long, Ptr, Flags], // kexec_load
+    &[Flags, Int, Ptr, Flags, Ptr], // waitid
+    &[Str, Str, Ptr, Long, Int], // add_key
+    &[Str, Str, Str, Int], // request_key
+    &[Flags, Long, Long, Long, Long], // keyctl
+    &[Flags, Int, Int], // ioprio_set
+    &[Flags, Int], // ioprio_get
+    &[], // inotify_init
+    &[Fd, Str, Flags], // inotify_add_watch
+    &[Fd, Int], // inotify_rm_watch
+    &[Int, Long, Ptr, Ptr], // migrate_pages
+    &[Fd, Str, Flags, Flags], // openat
+    &[Fd, Str, Flags], // mkdirat
+    &[Fd, Str, Flags, Long], // mknodat
+    &[Fd, Str, Int, Int, Flags], // fchownat
+    &[Fd, Str, Ptr], // futimesat
+    &[Fd, Str, Struct, Flags], // newfstatat
+    &[Fd, Str, Flags], // unlinkat
+    &[Fd, Str, Fd, Str], // renameat
+    &[Fd, Str, Fd, Str, Flags], // linkat
+    &[Str, Fd, Str], // symlinkat
+    &[Fd, Str, Ptr, Long], // readlinkat
+    &[Fd, Str, Flags], // fchmodat
+    &[Fd, Str, Flags], // faccessat
+    &[Int, Ptr, Ptr, Ptr, Struct, Ptr], // pselect6
+    &[Struct, Long, Struct, Ptr, Long], // ppoll
+    &[Flags], // unshare
+    &[Ptr, Long], // set_robust_list
+    &[Int, Ptr, Ptr], // get_robust_list
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // splice
+    &[Fd, Fd, Long, Flags], // tee
+    &[Fd, Long, Long, Flags], // sync_file_range
+    &[Fd, Struct, Long, Flags], // vmsplice
+    &[Int, Long, Ptr, Ptr, Ptr, Flags], // move_pages
+    &[Fd, Str, Ptr, Flags], // utimensat
+    &[Fd, Ptr, Int, Int, Ptr, Long], // epoll_pwait
+    &[Fd, Ptr, Long], // signalfd
+    &[Flags, Flags], // timerfd_create
+    &[Int], // eventfd
+    &[Fd, Flags, Long, Long], // fallocate
+    &[Fd, Flags, Struct, Ptr], // timerfd_settime
+    &[Fd, Ptr], // timerfd_gettime
+    &[Fd, Ptr, Ptr, Flags], // accept4
+    &[Fd, Ptr, Long, Flags], // signalfd4
+    &[Int, Flags], // eventfd2
+    &[Flags], // epoll_create1
+    &[Fd, Fd, Flags], // dup3
+    &[Ptr, Flags], // pipe2
+    &[Flags], // inotify_init1
+    &[Fd, Struct, Long, Long], // preadv
+    &[Fd, Struct, Long, Long], // pwritev
+    &[Int, Int, Int, Ptr], // rt_tgsigqueueinfo
+    &[Ptr, Int, Int, Fd, Flags], // perf_event_open
+    &[Fd, Ptr, Int, Flags, Struct], // recvmmsg
+    &[Flags, Flags], // fanotify_init
+    &[Fd, Flags, Long, Fd, Str], // fanotify_mark
+    &[Int, Flags, Ptr, Ptr], // prlimit64
+    &[Fd, Str, Ptr, Ptr, Flags], // name_to_handle_at
+    &[Fd, Ptr, Flags], // open_by_handle_at
+    &[Flags, Ptr], // clock_adjtime
+    &[Fd], // syncfs
+    &[Fd, Ptr, Int, Flags], // sendmmsg
+    &[Fd, Flags], // setns
+    &[Ptr, Ptr, Ptr], // getcpu
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_readv
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_writev
+    &[Int, Int, Flags, Long, Long], // kcmp
+    &[Fd, Str, Flags], // finit_module
+    &[Int, Ptr, Flags], // sched_setattr
+    &[Int, Ptr, Int, Flags], // sched_getattr
+    &[Fd, Str, Fd, Str, Flags], // renameat2
+    &[Flags, Flags, Ptr], // seccomp
+    &[Ptr, Long, Flags], // getrandom
+    &[Str, Flags], // memfd_create
+    &[Fd, Fd, Long, Str, Flags], // kexec_file_load
+    &[Flags, Ptr, Long], // bpf
+    &[Fd, Str, StrArray, Env, Flags], // execveat
+    &[Flags], // userfaultfd
+    &[Flags, Flags], // membarrier
+    &[Ptr, Long, Flags], // mlock2
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // copy_file_range
+    &[Fd, Struct, Long, Long, Flags], // preadv2
+    &[Fd, Struct, Long, Long, Flags], // pwritev2
+    &[Ptr, Long, Flags, Int], // pkey_mprotect
+    &[Flags, Flags], // pkey_alloc
+    &[Int], // pkey_free
+    &[Fd, Str, Flags, Flags, Ptr], // statx
+    &[Ptr, Long, Long, Ptr, Ptr, Ptr], // io_pgetevents
+    &[Ptr, Long, Flags, Flags], // rseq
+    &[], // 335
+    &[], // 336
+    &[], // 337
+    &[], // 338
+    &[], // 339
+    &[], // 340
+    &[], // 341
+    &[], // 342
+    &[], // 343
+    &[], // 344
+    &[], // 345
+    &[], // 346
+    &[], // 347
+    &[], // 348
+    &[], // 349
+    &[], // 350
+    &[], // 351
+    &[], // 352
+    &[], // 353
+    &[], // 354
+    &[], // 355
+    &[], // 356
+    &[], // 357
+    &[], // 358
+    &[], // 359
+    &[], // 360
+    &[], // 361
+    &[], // 362
+    &[], // 363
+    &[], // 364
+    &[], // 365
+    &[], // 366
+    &[], // 367
+    &[], // 368
+    &[], // 369
+    &[], // 370
+    &[], // 371
+    &[], // 372
+    &[], // 373
+    &[], // 374
+    &[], // 375
+    &[], // 376
+    &[], // 377
+    &[], // 378
+    &[], // 379
+    &[], // 380
+    &[], // 381
+    &[], // 382
+    &[], // 383
+    &[], // 384
+    &[], // 385
+    &[], // 386
+    &[], // 387
+    &[], // 388
+    &[], // 389
+    &[], // 390
+    &[], // 391
+    &[], // 392
+    &[], // 393
+    &[], // 394
+    &[], // 395
+    &[], // 396
+    &[], // 397
+    &[], // 398
+    &[], // 399
+    &[], // 400
+    &[], // 401
+    &[], // 402
+    &[], // 403
+    &[], // 404
+    &[], // 405
+    &[], // 406
+    &[], // 407
+    &[], // 408
+    &[], // 409
+    &[], // 410
+    &[], // 411
+    &[], // 412
+    &[], // 413
+    &[], // 414
+    &[], // 415
+    &[], // 416
+    &[], // 417
+    &[], // 418
+    &[], // 419
+    &[], // 420
+    &[], // 421
+    &[], // 422
+    &[], // 423
+    &[Fd, Int, Ptr, Flags], // pidfd_send_signal
+    &[Long, Ptr], // io_uring_setup
+    &[Fd, Long, Long, Flags, Ptr, Long], // io_uring_enter
+    &[Fd, Flags, Ptr, Long], // io_uring_register
+    &[Fd, Str, Flags], // open_tree
+    &[Fd, Str, Fd, Str, Flags], // move_mount
+    &[Str, Flags], // fsopen
+    &[Fd, Flags, Str, Ptr, Int], // fsconfig
+    &[Fd, Flags, Flags], // fsmount
+    &[Fd, Str, Flags], // fspick
+    &[Int, Flags], // pidfd_open
+    &[Ptr, Long], // clone3
+    &[Long, Long, Flags], // close_range
+    &[Fd, Str, Struct, Long], // openat2
+    &[Fd, Int, Flags], // pidfd_getfd
+    &[Fd, Str, Flags, Flags], // faccessat2
+    &[Fd, Struct, Long, Int, Flags], // process_madvise
+    &[Fd, Ptr, Int, Struct, Ptr, Long], // epoll_pwait2
+    &[Fd, Str, Flags, Ptr, Long], // mount_setattr
+    &[Fd, Flags, Int, Ptr], // quotactl_fd
+    &[Ptr, Long, Flags], // landlock_create_ruleset
+    &[Fd, Flags, Ptr, Flags], // landlock_add_rule
+    &[Fd, Flags], // landlock_restrict_self
+    &[Flags], // memfd_secret
+    &[Fd, Flags], // process_mrelease
+    &[Ptr, Long, Flags, Struct, Flags], // futex_waitv
+    &[Long, Long, Int, Flags], // set_mempolicy_home_node
+    &[Fd, Struct, Struct, Flags], // cachestat
+    &[Fd, Str, Flags, Flags], // fchmodat2
+    &[Ptr, Long, Flags], // map_shadow_stack
+    &[Ptr, Long, Int, Flags], // futex_wake
+    &[Ptr, Long, Long, Flags, Struct, Int], // futex_wait
+    &[Struct, Flags, Int, Int], // futex_requeue
+    &[Struct, Ptr, Long, Flags], // statmount
+    &[Struct, Ptr, Long, Flags], // listmount
+    &[Int, Ptr, Ptr, Flags], // lsm_get_self_attr
+    &[Int, Ptr, Int, Flags], // lsm_set_self_attr
+    &[Ptr, Ptr, Flags], // lsm_list_modules
+    &[Ptr, Long, Flags], // mseal
+    &[Fd, Str, Flags, Str, Struct, Long], // setxattrat
+    &[Fd, Str, Flags, Str, Struct, Long], // getxattrat
+    &[Fd, Str, Flags, Ptr, Long], // listxattrat
+    &[Fd, Str, Flags, Str], // removexattrat
+];
+
+#[cfg(target_arch = "x86_64")]
+pub static I386_SYSTEM_CALL_ARGS: [&[ArgKind]; 467] = [
+    &[], // restart_syscall
+    &[Int], // exit
+    &[], // fork
+    &[Fd, Ptr, Long], // read
+    &[Fd, Ptr, Long], // write
+    &[Str, Flags, Flags], // open
+    &[Fd], // close
+    &[Int, Ptr, Flags], // waitpid
+    &[Str, Flags], // creat
+    &[Str, Str], // link
+    &[Str], // unlink
+    &[Str, StrArray, Env], // execve
+    &[Str], // chdir
+    &[Ptr], // time
+    &[Str, Flags, Long], // mknod
+    &[Str, Flags], // chmod
+    &[Str, Int, Int], // lchown
+    &[], // break
+    &[Str, Struct], // oldstat
+    &[Fd, Long, Flags], // lseek
+    &[], // getpid
+    &[Str, Str, Str, Flags, Ptr], // mount
+    &[Str], // umount
+    &[Int], // setuid
+    &[], // getuid
+    &[Ptr], // stime
+    &[Flags, Int, Ptr, Ptr], // ptrace
+    &[Int], // alarm
+    &[Fd, Struct], // oldfstat
+    &[], // pause
+    &[Str, Ptr], // utime
+    &[], // stty
+    &[], // gtty
+    &[Str, Flags], // access
+    &[Int], // nice
+    &[], // ftime
+    &[], // sync
+    &[Int, Flags], // kill
+    &[Str, Str], // rename
+    &[Str, Flags], // mkdir
+    &[Str], // rmdir
+    &[Fd], // dup
+    &[Ptr], // pipe
+    &[Ptr], // times
+    &[], // prof
+    &[Ptr], // brk
+    &[Int], // setgid
+    &[], // getgid
+    &[Int, Ptr], // signal
+    &[], // geteuid
+    &[], // getegid
+    &[Str], // acct
+    &[Str, Flags], // umount2
+    &[], // lock
+    &[Fd, Flags, Ptr], // ioctl
+    &[Fd, Flags, Long], // fcntl
+    &[], // mpx
+    &[Int, Int], // setpgid
+    &[Int, Long], // ulimit
+    &[Ptr], // oldolduname
+    &[Flags], // umask
+    &[Str], // chroot
+    &[Long, Ptr], // ustat
+    &[Fd, Fd], // dup2
+    &[], // getppid
+    &[], // getpgrp
+    &[], // setsid
+    &[Int, Struct, Struct], // sigaction
+    &[], // sgetmask
+    &[Long], // ssetmask
+    &[Int, Int], // setreuid
+    &[Int, Int], // setregid
+    &[Int, Int, Long], // sigsuspend
+    &[Ptr], // sigpending
+    &[Ptr, Long], // sethostname
+    &[Flags, Ptr], // setrlimit
+    &[Flags, Ptr], // getrlimit
+    &[Int, Ptr], // getrusage
+    &[Ptr, Ptr], // gettimeofday
+    &[Ptr, Ptr], // settimeofday
+    &[Int, Ptr], // getgroups
+    &[Int, Ptr], // setgroups
+    &[Ptr], // select
+    &[Str, Str], // symlink
+    &[Str, Struct], // oldlstat
+    &[Str, Ptr, Long], // readlink
+    &[Str], // uselib
+    &[Str, Flags], // swapon
+    &[Flags, Flags, Flags, Ptr], // reboot
+    &[Fd, Ptr, Int], // readdir
+    &[Ptr], // mmap
+    &[Ptr, Long], // munmap
+    &[Str, Long], // truncate
+    &[Fd, Long], // ftruncate
+    &[Fd, Flags], // fchmod
+    &[Fd, Int, Int], // fchown
+    &[Int, Int], // getpriority
+    &[Int, Int, Int], // setpriority
+    &[], // profil
+    &[Str, Ptr], // statfs
+    &[Fd, Ptr], // fstatfs
+    &[Long, Long, Int], // ioperm
+    &[Int, Ptr], // socketcall
+    &[Int, Ptr, Int], // syslog
+    &[Int, Struct, Struct], // setitimer
+    &[Int, Struct], // getitimer
+    &[Str, Struct], // stat
+    &[Str, Struct], // lstat
+    &[Fd, Struct], // fstat
+    &[Ptr], // olduname
+    &[Int], // iopl
+    &[], // vhangup
+    &[], // idle
+    &[Ptr], // vm86old
+    &[Int, Ptr, Flags, Ptr], // wait4
+    &[Str], // swapoff
+    &[Ptr], // sysinfo
+    &[Int, Int, Int, Int, Ptr, Long], // ipc
+    &[Fd], // fsync
+    &[], // sigreturn
+    &[Flags, Ptr, Ptr, Ptr, Long], // clone
+    &[Ptr, Long], // setdomainname
+    &[Ptr], // uname
+    &[Int, Ptr, Long], // modify_ldt
+    &[Ptr], // adjtimex
+    &[Ptr, Long, Flags], // mprotect
+    &[Flags, Struct, Struct], // sigprocmask
+    &[Str, Long], // create_module
+    &[Ptr, Long, Str], // init_module
+    &[Str, Flags], // delete_module
+    &[Ptr], // get_kernel_syms
+    &[Flags, Str, Int, Ptr], // quotactl
+    &[Int], // getpgid
+    &[Fd], // fchdir
+    &[Int, Long], // bdflush
+    &[Int, Long, Long], // sysfs
+    &[Flags], // personality
+    &[], // afs_syscall
+    &[Int], // setfsuid
+    &[Int], // setfsgid
+    &[Fd, Long, Long, Ptr, Flags], // _llseek
+    &[Fd, Ptr, Long], // getdents
+    &[Int, Ptr, Ptr, Ptr, Struct], // _newselect
+    &[Fd, Flags], // flock
+    &[Ptr, Long, Flags], // msync
+    &[Fd, Struct, Long], // readv
+    &[Fd, Struct, Long], // writev
+    &[Int], // getsid
+    &[Fd], // fdatasync
+    &[Ptr], // _sysctl
+    &[Ptr, Long], // mlock
+    &[Ptr, Long], // munlock
+    &[Flags], // mlockall
+    &[], // munlockall
+    &[Int, Ptr], // sched_setparam
+    &[Int, Ptr], // sched_getparam
+    &[Int, Flags, Ptr], // sched_setscheduler
+    &[Int], // sched_getscheduler
+    &[], // sched_yield
+    &[Flags], // sched_get_priority_max
+    &[Flags], // sched_get_priority_min
+    &[Int, Struct], // sched_rr_get_interval
+    &[Struct, Struct], // nanosleep
+    &[Ptr, Long, Long, Flags, Ptr], // mremap
+    &[Int, Int, Int], // setresuid
+    &[Ptr, Ptr, Ptr], // getresuid
+    &[Long, Ptr], // vm86
+    &[Str, Flags, Ptr, Long, Ptr], // query_module
+    &[Struct, Int, Flags], // poll
+    &[Int, Ptr, Ptr], // nfsservctl
+    &[Int, Int, Int], // setresgid
+    &[Ptr, Ptr, Ptr], // getresgid
+    &[Flags, Long, Long, Long, Long], // prctl
+    &[], // rt_sigreturn
+    &[Int, Struct, Struct, Long], // rt_sigaction
+    &[Flags, Struct, Struct, Long], // rt_sigprocmask
+    &[Ptr, Long], // rt_sigpending
+    &[Ptr, Ptr, Struct, Long], // rt_sigtimedwait
+    &[Int, Flags, Ptr], // rt_sigqueueinfo
+    &[Ptr, Long], // rt_sigsuspend
+    &[Fd, Ptr, Long, Long], // pread64
+    &[Fd, Ptr, Long, Long], // pwrite64
+    &[Str, Int, Int], // chown
+    &[Ptr, Long], // getcwd
+    &[Ptr, Ptr], // capget
+    &[Ptr, Ptr], // capset
+    &[Ptr, Ptr], // sigaltstack
+    &[Fd, Fd, Ptr, Long], // sendfile
+    &[], // getpmsg
+    &[], // putpmsg
+    &[], // vfork
+    &[Flags, Ptr], // ugetrlimit
+    &[Ptr, Long, Flags, Flags, Fd, Long], // mmap2
+    &[Str, Long], // truncate64
+    &[Fd, Long], // ftruncate64
+    &[Str, Struct], // stat64
+    &[Str, Struct], // lstat64
+    &[Fd, Struct], // fstat64
+    &[Str, Int, Int], // lchown32
+    &[], // getuid32
+    &[], // getgid32
+    &[], // geteuid32
+    &[], // getegid32
+    &[Int, Int], // setreuid32
+    &[Int, Int], // setregid32
+    &[Int, Ptr], // getgroups32
+    &[Int, Ptr], // setgroups32
+    &[Fd, Int, Int], // fchown32
+    &[Int, Int, Int], // setresuid32
+    &[Ptr, Ptr, Ptr], // getresuid32
+    &[Int, Int, Int], // setresgid32
+    &[Ptr, Ptr, Ptr], // getresgid32
+    &[Str, Int, Int], // chown32
+    &[Int], // setuid32
+    &[Int], // setgid32
+    &[Int], // setfsuid32
+    &[Int], // setfsgid32
+    &[Str, Str], // pivot_root
+    &[Ptr, Long, Ptr], // mincore
+    &[Ptr, Long, Flags], // madvise
+    &[Fd, Ptr, Long], // getdents64
+    &[Fd, Flags, Long], // fcntl64
+    &[], // 222
+    &[], // 223
+    &[], // gettid
+    &[Fd, Long, Long], // readahead
+    &[Str, Str, Ptr, Long, Long], // setxattr
+    &[Str, Str, Ptr, Long, Long], // lsetxattr
+    &[Fd, Str, Ptr, Long, Long], // fsetxattr
+    &[Str, Str, Ptr, Long], // getxattr
+    &[Str, Str, Ptr, Long], // lgetxattr
+    &[Fd, Str, Ptr, Long], // fgetxattr
+    &[Str, Ptr, Long], // listxattr
+    &[Str, Ptr, Long], // llistxattr
+    &[Fd, Ptr, Long], // flistxattr
+    &[Str, Str], // removexattr
+    &[Str, Str], // lremovexattr
+    &[Fd, Str], // fremovexattr
+    &[Int, Flags], // tkill
+    &[Fd, Fd, Ptr, Long], // sendfile64
+    &[Ptr, Flags, Int, Struct, Ptr, Int], // futex
+    &[Int, Long, Ptr], // sched_setaffinity
+    &[Int, Long, Ptr], // sched_getaffinity
+    &[Ptr], // set_thread_area
+    &[Ptr], // get_thread_area
+    &[Int, Ptr], // io_setup
+    &[Long], // io_destroy
+    &[Long, Long, Long, Ptr, Struct], // io_getevents
+    &[Long, Long, Ptr], // io_submit
+    &[Long, Ptr, Ptr], // io_cancel
+    &[Fd, Long, Long, Flags], // fadvise64
+    &[], // 251
+    &[Int], // exit_group
+    &[Long, Ptr, Long], // lookup_dcookie
+    &[Int], // epoll_create
+    &[Fd, Flags, Fd, Ptr], // epoll_ctl
+    &[Fd, Ptr, Int, Int], // epoll_wait
+    &[Ptr, Long, Long, Long, Long], // remap_file_pages
+    &[Ptr], // set_tid_address
+    &[Flags, Ptr, Ptr], // timer_create
+    &[Int, Flags, Struct, Ptr], // timer_settime
+    &[Int, Ptr], // timer_gettime
+    &[Int], // timer_getoverrun
+    &[Int], // timer_delete
+    &[Flags, Struct], // clock_settime
+    &[Flags, Ptr], // clock_gettime
+    &[Flags, Ptr], // clock_getres
+    &[Flags, Flags, Struct, Ptr], // clock_nanosleep
+    &[Str, Long, Struct], // statfs64
+    &[Fd, Long, Struct], // fstatfs64
+    &[Int, Int, Flags], // tgkill
+    &[Str, Ptr], // utimes
+    &[Fd, Long, Long, Flags], // fadvise64_64
+    &[], // vserver
+    &[Ptr, Long, Flags, Ptr, Long], // mbind
+    &[Ptr, Ptr, Long, Long, Flags], // get_mempolicy
+    &[Flags, Ptr, Long], // set_mempolicy
+    &[Str, Flags, Flags, Ptr], // mq_open
+    &[Str], // mq_unlink
+    &[Fd, Ptr, Long, Int, Struct], // mq_timedsend
+    &[Fd, Ptr, Long, Ptr, Struct], // mq_timedreceive
+    &[Fd, Ptr], // mq_notify
+    &[Fd, Ptr, Ptr], // mq_getsetattr
+    &[Long, Long, Ptr, Flags], // kexec_load
+    &[Flags, Int, Ptr, Flags, Ptr], // waitid
+    &[], // 285
+    &[Str, Str, Ptr, Long, Int], // add_key
+    &[Str, Str, Str, Int], // request_key
+    &[Flags, Long, Long, Long, Long], // keyctl
+    &[Flags, Int, Int], // ioprio_set
+    &[Flags, Int], // ioprio_get
+    &[], // inotify_init
+    &[Fd, Str, Flags], // inotify_add_watch
+    &[Fd, Int], // inotify_rm_watch
+    &[Int, Long, Ptr, Ptr], // migrate_pages
+    &[Fd, Str, Flags, Flags], // openat
+    &[Fd, Str, Flags], // mkdirat
+    &[Fd, Str, Flags, Long], // mknodat
+    &[Fd, Str, Int, Int, Flags], // fchownat
+    &[Fd, Str, Ptr], // futimesat
+    &[Fd, Str, Struct, Flags], // fstatat64
+    &[Fd, Str, Flags], // unlinkat
+    &[Fd, Str, Fd, Str], // renameat
+    &[Fd, Str, Fd, Str, Flags], // linkat
+    &[Str, Fd, Str], // symlinkat
+    &[Fd, Str, Ptr, Long], // readlinkat
+    &[Fd, Str, Flags], // fchmodat
+    &[Fd, Str, Flags], // faccessat
+    &[Int, Ptr, Ptr, Ptr, Struct, Ptr], // pselect6
+    &[Struct, Int, Struct, Ptr, Long], // ppoll
+    &[Flags], // unshare
+    &[Ptr, Long], // set_robust_list
+    &[Int, Ptr, Ptr], // get_robust_list
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // splice
+    &[Fd, Long, Long, Flags], // sync_file_range
+    &[Fd, Fd, Long, Flags], // tee
+    &[Fd, Struct, Long, Flags], // vmsplice
+    &[Int, Long, Ptr, Ptr, Ptr, Flags], // move_pages
+    &[Ptr, Ptr, Ptr], // getcpu
+    &[Fd, Ptr, Int, Int, Ptr, Long], // epoll_pwait
+    &[Fd, Str, Ptr, Flags], // utimensat
+    &[Fd, Ptr, Long], // signalfd
+    &[Flags, Flags], // timerfd_create
+    &[Int], // eventfd
+    &[Fd, Flags, Long, Long], // fallocate
+    &[Fd, Flags, Struct, Ptr], // timerfd_settime
+    &[Fd, Ptr], // timerfd_gettime
+    &[Fd, Ptr, Long, Flags], // signalfd4
+    &[Int, Flags], // eventfd2
+    &[Flags], // epoll_create1
+    &[Fd, Fd, Flags], // dup3
+    &[Ptr, Flags], // pipe2
+    &[Flags], // inotify_init1
+    &[Fd, Struct, Long, Long], // preadv
+    &[Fd, Struct, Long, Long], // pwritev
+    &[Int, Int, Flags, Ptr], // rt_tgsigqueueinfo
+    &[Ptr, Int, Int, Fd, Flags], // perf_event_open
+    &[Fd, Ptr, Int, Flags, Struct], // recvmmsg
+    &[Flags, Flags], // fanotify_init
+    &[Fd, Flags, Long, Fd, Str], // fanotify_mark
+    &[Int, Flags, Ptr, Ptr], // prlimit64
+    &[Fd, Str, Ptr, Ptr, Flags], // name_to_handle_at
+    &[Fd, Ptr, Flags], // open_by_handle_at
+    &[Flags, Ptr], // clock_adjtime
+    &[Fd], // syncfs
+    &[Fd, Ptr, Int, Flags], // sendmmsg
+    &[Fd, Flags], // setns
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_readv
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_writev
+    &[Int, Int, Flags, Long, Long], // kcmp
+    &[Fd, Str, Flags], // finit_module
+    &[Int, Ptr, Flags], // sched_setattr
+    &[Int, Ptr, Int, Flags], // sched_getattr
+    &[Fd, Str, Fd, Str, Flags], // renameat2
+    &[Flags, Flags, Ptr], // seccomp
+    &[Ptr, Long, Flags], // getrandom
+    &[Str, Flags], // memfd_create
+    &[Flags, Ptr, Long], // bpf
+    &[Fd, Str, StrArray, Env, Flags], // execveat
+    &[Flags, Flags, Int], // socket
+    &[Flags, Flags, Int, Ptr], // socketpair
+    &[Fd, Struct, Long], // bind
+    &[Fd, Struct, Long], // connect
+    &[Fd, Int], // listen
+    &[Fd, Ptr, Ptr, Flags], // accept4
+    &[Fd, Flags, Flags, Ptr, Ptr], // getsockopt
+    &[Fd, Flags, Flags, Ptr, Long], // setsockopt
+    &[Fd, Ptr, Ptr], // getsockname
+    &[Fd, Ptr, Ptr], // getpeername
+    &[Fd, Ptr, Long, Flags, Struct, Long], // sendto
+    &[Fd, Struct, Flags], // sendmsg
+    &[Fd, Ptr, Long, Flags, Ptr, Ptr], // recvfrom
+    &[Fd, Struct, Flags], // recvmsg
+    &[Fd, Flags], // shutdown
+    &[Flags], // userfaultfd
+    &[Flags, Flags], // membarrier
+    &[Ptr, Long, Flags], // mlock2
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // copy_file_range
+    &[Fd, Struct, Long, Long, Flags], // preadv2
+    &[Fd, Struct, Long, Long, Flags], // pwritev2
+    &[Ptr, Long, Flags, Int], // pkey_mprotect
+    &[Flags, Flags], // pkey_alloc
+    &[Int], // pkey_free
+    &[Fd, Str, Flags, Flags, Ptr], // statx
+    &[Flags, Long], // arch_prctl
+    &[Ptr, Long, Long, Ptr, Ptr, Ptr], // io_pgetevents
+    &[Ptr, Long, Flags, Flags], // rseq
+    &[], // 387
+    &[], // 388
+    &[], // 389
+    &[], // 390
+    &[], // 391
+    &[], // 392
+    &[Int, Int, Flags], // semget
+    &[Int, Int, Flags, Ptr], // semctl
+    &[Int, Long, Flags], // shmget
+    &[Int, Flags, Ptr], // shmctl
+    &[Int, Ptr, Flags], // shmat
+    &[Ptr], // shmdt
+    &[Int, Flags], // msgget
+    &[Int, Ptr, Long, Flags], // msgsnd
+    &[Int, Ptr, Long, Long, Flags], // msgrcv
+    &[Int, Flags, Ptr], // msgctl
+    &[Flags, Ptr], // clock_gettime64
+    &[Flags, Struct], // clock_settime64
+    &[Flags, Ptr], // clock_adjtime64
+    &[Flags, Ptr], // clock_getres_time64
+    &[Flags, Flags, Struct, Ptr], // clock_nanosleep_time64
+    &[Int, Ptr], // timer_gettime64
+    &[Int, Flags, Struct, Ptr], // timer_settime64
+    &[Fd, Ptr], // timerfd_gettime64
+    &[Fd, Flags, Struct, Ptr], // timerfd_settime64
+    &[Fd, Str, Ptr, Flags], // utimensat_time64
+    &[Int, Ptr, Ptr, Ptr, Struct, Ptr], // pselect6_time64
+    &[Struct, Int, Struct, Ptr, Long], // ppoll_time64
+    &[], // 415
+    &[Ptr, Long, Long, Ptr, Ptr, Ptr], // io_pgetevents_time64
+    &[Fd, Ptr, Int, Flags, Struct], // recvmmsg_time64
+    &[Fd, Ptr, Long, Int, Struct], // mq_timedsend_time64
+    &[Fd, Ptr, Long, Ptr, Struct], // mq_timedreceive_time64
+    &[Int, Ptr, Long, Struct], // semtimedop_time64
+    &[Ptr, Ptr, Struct, Long], // rt_sigtimedwait_time64
+    &[Ptr, Flags, Int, Struct, Ptr, Int], // futex_time64
+    &[Int, Struct], // sched_rr_get_interval_time64
+    &[Fd, Int, Ptr, Flags], // pidfd_send_signal
+    &[Long, Ptr], // io_uring_setup
+    &[Fd, Long, Long, Flags, Ptr, Long], // io_uring_enter
+    &[Fd, Flags, Ptr, Long], // io_uring_register
+    &[Fd, Str, Flags], // open_tree
+    &[Fd, Str, Fd, Str, Flags], // move_mount
+    &[Str, Flags], // fsopen
+    &[Fd, Flags, Str, Ptr, Int], // fsconfig
+    &[Fd, Flags, Flags], // fsmount
+    &[Fd, Str, Flags], // fspick
+    &[Int, Flags], // pidfd_open
+    &[Ptr, Long], // clone3
+    &[Long, Long, Flags], // close_range
+    &[Fd, Str, Struct, Long], // openat2
+    &[Fd, Int, Flags], // pidfd_getfd
+    &[Fd, Str, Flags, Flags], // faccessat2
+    &[Fd, Struct, Long, Int, Flags], // process_madvise
+    &[Fd, Ptr, Int, Struct, Ptr, Long], // epoll_pwait2
+    &[Fd, Str, Flags, Ptr, Long], // mount_setattr
+    &[Fd, Flags, Int, Ptr], // quotactl_fd
+    &[Ptr, Long, Flags], // landlock_create_ruleset
+    &[Fd, Flags, Ptr, Flags], // landlock_add_rule
+    &[Fd, Flags], // landlock_restrict_self
+    &[Flags], // memfd_secret
+    &[Fd, Flags], // process_mrelease
+    &[Ptr, Long, Flags, Struct, Flags], // futex_waitv
+    &[Long, Long, Int, Flags], // set_mempolicy_home_node
+    &[Fd, Struct, Struct, Flags], // cachestat
+    &[Fd, Str, Flags, Flags], // fchmodat2
+    &[], // 453
+    &[Ptr, Long, Int, Flags], // futex_wake
+    &[Ptr, Long, Long, Flags, Struct, Int], // futex_wait
+    &[Struct, Flags, Int, Int], // futex_requeue
+    &[Struct, Ptr, Long, Flags], // statmount
+    &[Struct, Ptr, Long, Flags], // listmount
+    &[Int, Ptr, Ptr, Flags], // lsm_get_self_attr
+    &[Int, Ptr, Int, Flags], // lsm_set_self_attr
+    &[Ptr, Ptr, Flags], // lsm_list_modules
+    &[Ptr, Long, Flags], // mseal
+    &[Fd, Str, Flags, Str, Struct, Long], // setxattrat
+    &[Fd, Str, Flags, Str, Struct, Long], // getxattrat
+    &[Fd, Str, Flags, Ptr, Long], // listxattrat
+    &[Fd, Str, Flags, Str], // removexattrat
+];
+
+#[cfg(target_arch = "aarch64")]
+pub static AARCH64_SYSTEM_CALL_ARGS: [&[ArgKind]; 467] = [
+    &[Int, Ptr], // io_setup
+    &[Long], // io_destroy
+    &[Long, Long, Ptr], // io_submit
+    &[Long, Ptr, Ptr], // io_cancel
+    &[Long, Long, Long, Ptr, Struct], // io_getevents
+    &[Str, Str, Ptr, Long, Long], // setxattr
+    &[Str, Str, Ptr, Long, Long], // lsetxattr
+    &[Fd, Str, Ptr, Long, Long], // fsetxattr
+    &[Str, Str, Ptr, Long], // getxattr
+    &[Str, Str, Ptr, Long], // lgetxattr
+    &[Fd, Str, Ptr, Long], // fgetxattr
+    &[Str, Ptr, Long], // listxattr
+    &[Str, Ptr, Long], // llistxattr
+    &[Fd, Ptr, Long], // flistxattr
+    &[Str, Str], // removexattr
+    &[Str, Str], // lremovexattr
+    &[Fd, Str], // fremovexattr
+    &[Ptr, Long], // getcwd
+    &[Long, Ptr, Long], // lookup_dcookie
+    &[Int, Flags], // eventfd2
+    &[Flags], // epoll_create1
+    &[Fd, Flags, Fd, Ptr], // epoll_ctl
+    &[Fd, Ptr, Int, Int, Ptr, Long], // epoll_pwait
+    &[Fd], // dup
+    &[Fd, Fd, Flags], // dup3
+    &[Fd, Flags, Long], // fcntl
+    &[Flags], // inotify_init1
+    &[Fd, Str, Flags], // inotify_add_watch
+    &[Fd, Int], // inotify_rm_watch
+    &[Fd, Flags, Ptr], // ioctl
+    &[Flags, Int, Int], // ioprio_set
+    &[Flags, Int], // ioprio_get
+    &[Fd, Flags], // flock
+    &[Fd, Str, Flags, Long], // mknodat
+    &[Fd, Str, Flags], // mkdirat
+    &[Fd, Str, Flags], // unlinkat
+    &[Str, Fd, Str], // symlinkat
+    &[Fd, Str, Fd, Str, Flags], // linkat
+    &[Fd, Str, Fd, Str], // renameat
+    &[Str, Flags], // umount2
+    &[Str, Str, Str, Flags, Ptr], // mount
+    &[Str, Str], // pivot_root
+    &[Int, Ptr, Ptr], // nfsservctl
+    &[Str, Ptr], // statfs
+    &[Fd, Ptr], // fstatfs
+    &[Str, Long], // truncate
+    &[Fd, Long], // ftruncate
+    &[Fd, Flags, Long, Long], // fallocate
+    &[Fd, Str, Flags], // faccessat
+    &[Str], // chdir
+    &[Fd], // fchdir
+    &[Str], // chroot
+    &[Fd, Flags], // fchmod
+    &[Fd, Str, Flags], // fchmodat
+    &[Fd, Str, Int, Int, Flags], // fchownat
+    &[Fd, Int, Int], // fchown
+    &[Fd, Str, Flags, Flags], // openat
+    &[Fd], // close
+    &[], // vhangup
+    &[Ptr, Flags], // pipe2
+    &[Flags, Str, Int, Ptr], // quotactl
+    &[Fd, Ptr, Long], // getdents64
+    &[Fd, Long, Flags], // lseek
+    &[Fd, Ptr, Long], // read
+    &[Fd, Ptr, Long], // write
+    &[Fd, Struct, Long], // readv
+    &[Fd, Struct, Long], // writev
+    &[Fd, Ptr, Long, Long], // pread64
+    &[Fd, Ptr, Long, Long], // pwrite64
+    &[Fd, Struct, Long, Long], // preadv
+    &[Fd, Struct, Long, Long], // pwritev
+    &[Fd, Fd, Ptr, Long], // sendfile
+    &[Int, Ptr, Ptr, Ptr, Struct, Ptr], // pselect6
+    &[Struct, Int, Struct, Ptr, Long], // ppoll
+    &[Fd, Ptr, Long, Flags], // signalfd4
+    &[Fd, Struct, Long, Flags], // vmsplice
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // splice
+    &[Fd, Fd, Long, Flags], // tee
+    &[Fd, Str, Ptr, Long], // readlinkat
+    &[Fd, Str, Struct, Flags], // newfstatat
+    &[Fd, Struct], // fstat
+    &[], // sync
+    &[Fd], // fsync
+    &[Fd], // fdatasync
+    &[Fd, Long, Long, Flags], // sync_file_range
+    &[Flags, Flags], // timerfd_create
+    &[Fd, Flags, Struct, Ptr], // timerfd_settime
+    &[Fd, Ptr], // timerfd_gettime
+    &[Fd, Str, Ptr, Flags], // utimensat
+    &[Str], // acct
+    &[Ptr, Ptr], // capget
+    &[Ptr, Ptr], // capset
+    &[Flags], // personality
+    &[Int], // exit
+    &[Int], // exit_group
+    &[Flags, Int, Ptr, Flags, Ptr], // waitid
+    &[Ptr], // set_tid_address
+    &[Flags], // unshare
+    &[Ptr, Flags, Int, Struct, Ptr, Int], // futex
+    &[Ptr, Long], // set_robust_list
+    &[Int, Ptr, Ptr], // get_robust_list
+    &[Struct, Struct], // nanosleep
+    &[Int, Struct], // getitimer
+    &[Int, Struct, Struct], // setitimer
+    &[Long, Long, Ptr, Flags], // kexec_load
+    &[Ptr, Long, Str], // init_module
+    &[Str, Flags], // delete_module
+    &[Flags, Ptr, Ptr], // timer_create
+    &[Int, Ptr], // timer_gettime
+    &[Int], // timer_getoverrun
+    &[Int, Flags, Struct, Ptr], // timer_settime
+    &[Int], // timer_delete
+    &[Flags, Struct], // clock_settime
+    &[Flags, Ptr], // clock_gettime
+    &[Flags, Ptr], // clock_getres
+    &[Flags, Flags, Struct, Ptr], // clock_nanosleep
+    &[Int, Ptr, Int], // syslog
+    &[Flags, Int, Ptr, Ptr], // ptrace
+    &[Int, Ptr], // sched_setparam
+    &[Int, Flags, Ptr], // sched_setscheduler
+    &[Int], // sched_getscheduler
+    &[Int, Ptr], // sched_getparam
+    &[Int, Long, Ptr], // sched_setaffinity
+    &[Int, Long, Ptr], // sched_getaffinity
+    &[], // sched_yield
+    &[Flags], // sched_get_priority_max
+    &[Flags], // sched_get_priority_min
+    &[Int, Struct], // sched_rr_get_interval
+    &[], // restart_syscall
+    &[Int, Flags], // kill
+    &[Int, Flags], // tkill
+    &[Int, Int, Flags], // tgkill
+    &[Ptr, Ptr], // sigaltstack
+    &[Ptr, Long], // rt_sigsuspend
+    &[Int, Struct, Struct, Long], // rt_sigaction
+    &[Flags, Struct, Struct, Long], // rt_sigprocmask
+    &[Ptr, Long], // rt_sigpending
+    &[Ptr, Ptr, Struct, Long], // rt_sigtimedwait
+    &[Int, Flags, Ptr], // rt_sigqueueinfo
+    &[], // rt_sigreturn
+    &[Int, Int, Int], // setpriority
+    &[Int, Int], // getpriority
+    &[Flags, Flags, Flags, Ptr], // reboot
+    &[Int, Int], // setregid
+    &[Int], // setgid
+    &[Int, Int], // setreuid
+    &[Int], // setuid
+    &[Int, Int, Int], // setresuid
+    &[Ptr, Ptr, Ptr], // getresuid
+    &[Int, Int, Int], // setresgid
+    &[Ptr, Ptr, Ptr], // getresgid
+    &[Int], // setfsuid
+    &[Int], // setfsgid
+    &[Ptr], // times
+    &[Int, Int], // setpgid
+    &[Int], // getpgid
+    &[Int], // getsid
+    &[], // setsid
+    &[Int, Ptr], // getgroups
+    &[Int, Ptr], // setgroups
+    &[Ptr], // uname
+    &[Ptr, Long], // sethostname
+    &[Ptr, Long], // setdomainname
+    &[Flags, Ptr], // getrlimit
+    &[Flags, Ptr], // setrlimit
+    &[Int, Ptr], // getrusage
+    &[Flags], // umask
+    &[Flags, Long, Long, Long, Long], // prctl
+    &[Ptr, Ptr, Ptr], // getcpu
+    &[Ptr, Ptr], // gettimeofday
+    &[Ptr, Ptr], // settimeofday
+    &[Ptr], // adjtimex
+    &[], // getpid
+    &[], // getppid
+    &[], // getuid
+    &[], // geteuid
+    &[], // getgid
+    &[], // getegid
+    &[], // gettid
+    &[Ptr], // sysinfo
+    &[Str, Flags, Flags, Ptr], // mq_open
+    &[Str], // mq_unlink
+    &[Fd, Ptr, Long, Int, Struct], // mq_timedsend
+    &[Fd, Ptr, Long, Ptr, Struct], // mq_timedreceive
+    &[Fd, Ptr], // mq_notify
+    &[Fd, Ptr, Ptr], // mq_getsetattr
+    &[Int, Flags], // msgget
+    &[Int, Flags, Ptr], // msgctl
+    &[Int, Ptr, Long, Long, Flags], // msgrcv
+    &[Int, Ptr, Long, Flags], // msgsnd
+    &[Int, Int, Flags], // semget
+    &[Int, Int, Flags, Ptr], // semctl
+    &[Int, Ptr, Long, Struct], // semtimedop
+    &[Int, Ptr, Long], // semop
+    &[Int, Long, Flags], // shmget
+    &[Int, Flags, Ptr], // shmctl
+    &[Int, Ptr, Flags], // shmat
+    &[Ptr], // shmdt
+    &[Flags, Flags, Int], // socket
+    &[Flags, Flags, Int, Ptr], // socketpair
+    &[Fd, Struct, Long], // bind
+    &[Fd, Int], // listen
+    &[Fd, Ptr, Ptr], // accept
+    &[Fd, Struct, Long], // connect
+    &[Fd, Ptr, Ptr], // getsockname
+    &[Fd, Ptr, Ptr], // getpeername
+    &[Fd, Ptr, Long, Flags, Struct, Long], // sendto
+    &[Fd, Ptr, Long, Flags, Ptr, Ptr], // recvfrom
+    &[Fd, Flags, Flags, Ptr, Long], // setsockopt
+    &[Fd, Flags, Flags, Ptr, Ptr], // getsockopt
+    &[Fd, Flags], // shutdown
+    &[Fd, Struct, Flags], // sendmsg
+    &[Fd, Struct, Flags], // recvmsg
+    &[Fd, Long, Long], // readahead
+    &[Ptr], // brk
+    &[Ptr, Long], // munmap
+    &[Ptr, Long, Long, Flags, Ptr], // mremap
+    &[Str, Str, Ptr, Long, Int], // add_key
+    &[Str, Str, Str, Int], // request_key
+    &[Flags, Long, Long, Long, Long], // keyctl
+    &[Flags, Ptr, Ptr, Ptr, Long], // clone
+    &[Str, StrArray, Env], // execve
+    &[Ptr, Long, Flags, Flags, Fd, Long], // mmap
+    &[Fd, Long, Long, Flags], // fadvise64
+    &[Str, Flags], // swapon
+    &[Str], // swapoff
+    &[Ptr, Long, Flags], // mprotect
+    &[Ptr, Long, Flags], // msync
+    &[Ptr, Long], // mlock
+    &[Ptr, Long], // munlock
+    &[Flags], // mlockall
+    &[], // munlockall
+    &[Ptr, Long, Ptr], // mincore
+    &[Ptr, Long, Flags], // madvise
+    &[Ptr, Long, Long, Long, Long], // remap_file_pages
+    &[Ptr, Long, Flags, Ptr, Long], // mbind
+    &[Ptr, Ptr, Long, Long, Flags], // get_mempolicy
+    &[Flags, Ptr, Long], // set_mempolicy
+    &[Int, Long, Ptr, Ptr], // migrate_pages
+    &[Int, Long, Ptr, Ptr, Ptr, Flags], // move_pages
+    &[Int, Int, Flags, Ptr], // rt_tgsigqueueinfo
+    &[Ptr, Int, Int, Fd, Flags], // perf_event_open
+    &[Fd, Ptr, Ptr, Flags], // accept4
+    &[Fd, Ptr, Int, Flags, Struct], // recvmmsg
+    &[], // 244
+    &[], // 245
+    &[], // 246
+    &[], // 247
+    &[], // 248
+    &[], // 249
+    &[], // 250
+    &[], // 251
+    &[], // 252
+    &[], // 253
+    &[], // 254
+    &[], // 255
+    &[], // 256
+    &[], // 257
+    &[], // 258
+    &[], // 259
+    &[Int, Ptr, Flags, Ptr], // wait4
+    &[Int, Flags, Ptr, Ptr], // prlimit64
+    &[Flags, Flags], // fanotify_init
+    &[Fd, Flags, Long, Fd, Str], // fanotify_mark
+    &[Fd, Str, Ptr, Ptr, Flags], // name_to_handle_at
+    &[Fd, Ptr, Flags], // open_by_handle_at
+    &[Flags, Ptr], // clock_adjtime
+    &[Fd], // syncfs
+    &[Fd, Flags], // setns
+    &[Fd, Ptr, Int, Flags], // sendmmsg
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_readv
+    &[Int, Struct, Long, Struct, Long, Flags], // process_vm_writev
+    &[Int, Int, Flags, Long, Long], // kcmp
+    &[Fd, Str, Flags], // finit_module
+    &[Int, Ptr, Flags], // sched_setattr
+    &[Int, Ptr, Int, Flags], // sched_getattr
+    &[Fd, Str, Fd, Str, Flags], // renameat2
+    &[Flags, Flags, Ptr], // seccomp
+    &[Ptr, Long, Flags], // getrandom
+    &[Str, Flags], // memfd_create
+    &[Flags, Ptr, Long], // bpf
+    &[Fd, Str, StrArray, Env, Flags], // execveat
+    &[Flags], // userfaultfd
+    &[Flags, Flags], // membarrier
+    &[Ptr, Long, Flags], // mlock2
+    &[Fd, Ptr, Fd, Ptr, Long, Flags], // copy_file_range
+    &[Fd, Struct, Long, Long, Flags], // preadv2
+    &[Fd, Struct, Long, Long, Flags], // pwritev2
+    &[Ptr, Long, Flags, Int], // pkey_mprotect
+    &[Flags, Flags], // pkey_alloc
+    &[Int], // pkey_free
+    &[Fd, Str, Flags, Flags, Ptr], // statx
+    &[Ptr, Long, Long, Ptr, Ptr, Ptr], // io_pgetevents
+    &[Ptr, Long, Flags, Flags], // rseq
+    &[Fd, Fd, Long, Str, Flags], // kexec_file_load
+    &[], // 295
+    &[], // 296
+    &[], // 297
+    &[], // 298
+    &[], // 299
+    &[], // 300
+    &[], // 301
+    &[], // 302
+    &[], // 303
+    &[], // 304
+    &[], // 305
+    &[], // 306
+    &[], // 307
+    &[], // 308
+    &[], // 309
+    &[], // 310
+    &[], // 311
+    &[], // 312
+    &[], // 313
+    &[], // 314
+    &[], // 315
+    &[], // 316
+    &[], // 317
+    &[], // 318
+    &[], // 319
+    &[], // 320
+    &[], // 321
+    &[], // 322
+    &[], // 323
+    &[], // 324
+    &[], // 325
+    &[], // 326
+    &[], // 327
+    &[], // 328
+    &[], // 329
+    &[], // 330
+    &[], // 331
+    &[], // 332
+    &[], // 333
+    &[], // 334
+    &[], // 335
+    &[], // 336
+    &[], // 337
+    &[], // 338
+    &[], // 339
+    &[], // 340
+    &[], // 341
+    &[], // 342
+    &[], // 343
+    &[], // 344
+    &[], // 345
+    &[], // 346
+    &[], // 347
+    &[], // 348
+    &[], // 349
+    &[], // 350
+    &[], // 351
+    &[], // 352
+    &[], // 353
+    &[], // 354
+    &[], // 355
+    &[], // 356
+    &[], // 357
+    &[], // 358
+    &[], // 359
+    &[], // 360
+    &[], // 361
+    &[], // 362
+    &[], // 363
+    &[], // 364
+    &[], // 365
+    &[], // 366
+    &[], // 367
+    &[], // 368
+    &[], // 369
+    &[], // 370
+    &[], // 371
+    &[], // 372
+    &[], // 373
+    &[], // 374
+    &[], // 375
+    &[], // 376
+    &[], // 377
+    &[], // 378
+    &[], // 379
+    &[], // 380
+    &[], // 381
+    &[], // 382
+    &[], // 383
+    &[], // 384
+    &[], // 385
+    &[], // 386
+    &[], // 387
+    &[], // 388
+    &[], // 389
+    &[], // 390
+    &[], // 391
+    &[], // 392
+    &[], // 393
+    &[], // 394
+    &[], // 395
+    &[], // 396
+    &[], // 397
+    &[], // 398
+    &[], // 399
+    &[], // 400
+    &[], // 401
+    &[], // 402
+    &[], // 403
+    &[], // 404
+    &[], // 405
+    &[], // 406
+    &[], // 407
+    &[], // 408
+    &[], // 409
+    &[], // 410
+    &[], // 411
+    &[], // 412
+    &[], // 413
+    &[], // 414
+    &[], // 415
+    &[], // 416
+    &[], // 417
+    &[], // 418
+    &[], // 419
+    &[], // 420
+    &[], // 421
+    &[], // 422
+    &[], // 423
+    &[Fd, Int, Ptr, Flags], // pidfd_send_signal
+    &[Long, Ptr], // io_uring_setup
+    &[Fd, Long, Long, Flags, Ptr, Long], // io_uring_enter
+    &[Fd, Flags, Ptr, Long], // io_uring_register
+    &[Fd, Str, Flags], // open_tree
+    &[Fd, Str, Fd, Str, Flags], // move_mount
+    &[Str, Flags], // fsopen
+    &[Fd, Flags, Str, Ptr, Int], // fsconfig
+    &[Fd, Flags, Flags], // fsmount
+    &[Fd, Str, Flags], // fspick
+    &[Int, Flags], // pidfd_open
+    &[Ptr, Long], // clone3
+    &[Long, Long, Flags], // close_range
+    &[Fd, Str, Struct, Long], // openat2
+    &[Fd, Int, Flags], // pidfd_getfd
+    &[Fd, Str, Flags, Flags], // faccessat2
+    &[Fd, Struct, Long, Int, Flags], // process_madvise
+    &[Fd, Ptr, Int, Struct, Ptr, Long], // epoll_pwait2
+    &[Fd, Str, Flags, Ptr, Long], // mount_setattr
+    &[Fd, Flags, Int, Ptr], // quotactl_fd
+    &[Ptr, Long, Flags], // landlock_create_ruleset
+    &[Fd, Flags, Ptr, Flags], // landlock_add_rule
+    &[Fd, Flags], // landlock_restrict_self
+    &[Flags], // memfd_secret
+    &[Fd, Flags], // process_mrelease
+    &[Ptr, Long, Flags, Struct, Flags], // futex_waitv
+    &[Long, Long, Int, Flags], // set_mempolicy_home_node
+    &[Fd, Struct, Struct, Flags], // cachestat
+    &[Fd, Str, Flags, Flags], // fchmodat2
+    &[Ptr, Long, Flags], // map_shadow_stack
+    &[Ptr, Long, Int, Flags], // futex_wake
+    &[Ptr, Long, Long, Flags, Struct, Int], // futex_wait
+    &[Struct, Flags, Int, Int], // futex_requeue
+    &[Struct, Ptr, Long, Flags], // statmount
+    &[Struct, Ptr, Long, Flags], // listmount
+    &[Int, Ptr, Ptr, Flags], // lsm_get_self_attr
+    &[Int, Ptr, Int, Flags], // lsm_set_self_attr
+    &[Ptr, Ptr, Flags], // lsm_list_modules
+    &[Ptr, Long, Flags], // mseal
+    &[Fd, Str, Flags, Str, Struct, Long], // setxattrat
+    &[Fd, Str, Flags, Str, Struct, Long], // getxattrat
+    &[Fd, Str, Flags, Ptr, Long], // listxattrat
+    &[Fd, Str, Flags, Str], // removexattrat
+];
@@ -0,0 +1,78 @@
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How to prefix trace lines with the time the call was made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timestamps {
+    /// Wall clock time, `12:34:56`.
+    Clock,
+    /// Wall clock time with microseconds, `12:34:56.123456`.
+    Precise,
+    /// Seconds and microseconds since the epoch, `1589012345.123456`.
+    Unix,
+    /// Seconds and microseconds since the trace started, `0.001234`.
+    Relative,
+}
+
+impl FromStr for Timestamps {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Timestamps, String> {
+        match s {
+            "clock" => Ok(Timestamps::Clock),
+            "precise" => Ok(Timestamps::Precise),
+            "unix" => Ok(Timestamps::Unix),
+            "relative" => Ok(Timestamps::Relative),
+            _ => Err(format!("unknown timestamp format {}", s)),
+        }
+    }
+}
+
+impl Timestamps {
+    pub fn format(self, at: SystemTime, start: SystemTime) -> String {
+        match self {
+            Timestamps::Clock => {
+                let (h, m, s) = local_time(at);
+                format!("{:02}:{:02}:{:02}", h, m, s)
+            },
+            Timestamps::Precise => {
+                let (h, m, s) = local_time(at);
+                format!("{:02}:{:02}:{:02}.{:06}", h, m, s, since(UNIX_EPOCH, at).subsec_micros())
+            },
+            Timestamps::Unix => seconds(since(UNIX_EPOCH, at)),
+            Timestamps::Relative => seconds(since(start, at)),
+        }
+    }
+}
+
+/// How long a call took, printed after its return value like strace's `-T`.
+pub fn duration(d: Duration) -> String {
+    format!("<{}>", seconds(d))
+}
+
+fn seconds(d: Duration) -> String {
+    format!("{}.{:06}", d.as_secs(), d.subsec_micros())
+}
+
+fn since(earlier: SystemTime, at: SystemTime) -> Duration {
+    at.duration_since(earlier).unwrap_or_default()
+}
+
+/// Hours, minutes and seconds of `at` in the local time zone.
+fn local_time(at: SystemTime) -> (i32, i32, i32) {
+    let secs = since(UNIX_EPOCH, at).as_secs() as libc::time_t;
+    unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&secs, &mut tm);
+        (tm.tm_hour, tm.tm_min, tm.tm_sec)
+    }
+}
+
+#[test]
+fn timestamps_test() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_589_012_345);
+    let at = start + Duration::from_micros(1_000_042);
+    assert_eq!(Timestamps::Unix.format(at, start), "1589012346.000042");
+    assert_eq!(Timestamps::Relative.format(at, start), "1.000042");
+    assert_eq!(duration(Duration::from_micros(123)), "<0.000123>");
+}
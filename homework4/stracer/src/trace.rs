@@ -1,134 +1,868 @@
 
-use crate::system_call_names::*;
+use crate::arch::{Arch, Regs};
+use crate::system_call_args::ArgKind;
+use crate::buffers;
+use crate::command::Command;
+use crate::events::{Event, ProcessEvent, SignalEvent, SyscallEvent};
+use crate::filter::Status;
+use crate::fds::{self, FdTable};
+use crate::flags;
+use crate::memory;
+use crate::json;
+use crate::inject::{Fault, Injection};
+use crate::output::Format;
+use crate::seccomp;
+use crate::signals;
+use crate::stack::Unwinder;
+use crate::structs;
+use crate::summary::Summary;
+use crate::timestamps;
+use crate::tree::{Exec, ProcessTree};
 use crate::util;
 use std::ffi::CString;
+use std::io;
 use std::collections::{HashSet, HashMap};
 use log::info;
 use nix::sys::{wait, ptrace, signal};
 use nix::unistd::*;
-use libc::{c_void, user_regs_struct};
-use super::TraceOption;
+use libc::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use super::{TraceConfig, TraceOption};
 
-const ADDRESS_LOWER_BOUND: i64 = 10000;
 const DEBUG_PTRACE_EVENT: [&str; 7] = [
     "PTRACE_EVENT_FORK", "PTRACE_EVENT_VFORK", "PTRACE_EVENT_CLONE",
     "PTRACE_EVENT_EXEC", "PTRACE_EVENT_VFORK_DONE", "PTRACE_EVENT_EXIT",
     "PTRACE_EVENT_SECCOMP"];
+const PTRACE_EVENT_STOP: i32 = 128;
+/// Longer string arrays are cut off with `...`, as strace does.
+const MAX_ARRAY: usize = 32;
 
-pub fn trace_prehook(regs: &user_regs_struct, pid: Pid) -> String {
-    let name = SYSTEM_CALL_NAMES[regs.orig_rax as usize].to_string();
-    let print_string = vec!["execve", "access", "stat", "lstat", "chdir"];
-    if print_string.contains(&name.as_str()) {
-        let arg_str = util::read_string(regs.rdi as *mut c_void, pid);
-        format!("{}(\"{}\")", name, arg_str)
-    } else {
-        format!("{}()", name)
+/// An argument of a call of `arch`, made by `pid`, as it is printed.
+pub fn format_arg(kind: ArgKind, value: u64, pid: Pid, arch: Arch) -> String {
+    match kind {
+        ArgKind::Int | ArgKind::Fd => format!("{}", value as i32),
+        ArgKind::Long => format!("{}", value as i64),
+        ArgKind::Flags if value == 0 => "0".to_string(),
+        ArgKind::Flags => format!("{:#x}", value),
+        ArgKind::Ptr | ArgKind::Struct | ArgKind::Str | ArgKind::StrArray | ArgKind::Env
+            if value == 0 => "NULL".to_string(),
+        ArgKind::Ptr | ArgKind::Struct => format!("{:#x}", value),
+        ArgKind::Str => match memory::read_string(pid, value, memory::PATH_MAX) {
+            Ok((string, truncated)) => buffers::escape(&string, truncated),
+            Err(_) => format!("{:#x}", value),
+        },
+        ArgKind::StrArray => match memory::read_pointer_array(pid, value, arch.word_size(), MAX_ARRAY + 1) {
+            Ok(pointers) => {
+                let mut strings = pointers.iter().take(MAX_ARRAY)
+                    .map(|&pointer| format_arg(ArgKind::Str, pointer, pid, arch))
+                    .collect::<Vec<String>>();
+                if pointers.len() > MAX_ARRAY {
+                    strings.push("...".to_string());
+                }
+                format!("[{}]", strings.join(", "))
+            },
+            Err(_) => format!("{:#x}", value),
+        },
+        ArgKind::Env => match memory::read_pointer_array(pid, value, arch.word_size(), usize::MAX) {
+            Ok(pointers) => format!("{:#x} /* {} vars */", value, pointers.len()),
+            Err(_) => format!("{:#x}", value),
+        },
     }
 }
 
-pub fn trace_posthook(regs: &user_regs_struct) -> String {
-    let retval = regs.rax as i64;
-    if retval > ADDRESS_LOWER_BOUND {
-        format!("{:#x}", retval)
-    } else {
-        format!("{}", retval)
+/// The arguments of the call `regs` is stopped at, decoded for printing.
+pub fn decode_args(regs: &Regs, pid: Pid) -> Vec<String> {
+    let name = regs.arch.syscall_name(regs.nr());
+    let args = regs.args();
+    regs.arch.syscall_args(regs.nr()).iter()
+        .enumerate()
+        .filter(|&(i, _)| !flags::omitted(regs.arch, name, i, &args))
+        .map(|(i, kind)| flags::decode(regs.arch, name, i, &args)
+             .unwrap_or_else(|| format_arg(*kind, args[i], pid, regs.arch)))
+        .collect()
+}
+
+pub fn trace_prehook(regs: &Regs, tid: Pid, pid: Pid, decode: bool) -> SyscallEvent {
+    let nr = regs.nr();
+    let raw_args = regs.args();
+    SyscallEvent {
+        pid,
+        tid,
+        arch: regs.arch,
+        nr,
+        name: regs.arch.syscall_name(nr),
+        args: if decode { decode_args(regs, tid) } else { Vec::new() },
+        raw_args: raw_args[..regs.arch.syscall_args(nr).len()].to_vec(),
+        retval: None,
+        entered_at: SystemTime::now(),
+        duration: None,
+        injected: false,
+        data: None,
+        data_truncated: false,
+        started: None,
+        stack: None,
+    }
+}
+
+pub fn trace_posthook(regs: &Regs, call: &mut SyscallEvent, elapsed: Duration) {
+    call.retval = Some(regs.retval());
+    call.duration = Some(elapsed);
+}
+
+/// Show the data of a read or write style call in place of its buffer
+/// address, once it is there: at entry for writes, at exit for reads.
+pub fn decode_data(call: &mut SyscallEvent, at_exit: bool, config: &TraceConfig) {
+    let arg = match buffers::data_arg(call.name) {
+        // The iovecs of other ABIs are not laid out like ours.
+        Some(arg) if arg.vectored && call.arch != Arch::native() => return,
+        Some(arg) if arg.at_exit == at_exit => arg,
+        _ => return,
+    };
+    let len = match call.retval {
+        Some(retval) if at_exit => if retval >= 0 { retval as u64 } else { return },
+        _ if arg.vectored => u64::MAX,
+        _ => call.raw_args[2],
+    };
+    let dump = config.hex_dump.contains(&(call.raw_args[0] as i32));
+    if let Ok(data) = buffers::read_data(call.tid, &call.raw_args, arg, len,
+                                         config.string_limit, dump) {
+        call.args[arg.buf] = data.text;
+        if dump {
+            call.data = Some(data.bytes);
+            call.data_truncated = data.truncated;
+        }
+    }
+}
+
+/// Follow each descriptor argument with what it refers to.
+pub fn decode_fds(call: &mut SyscallEvent, fds: &mut FdTable) {
+    let kinds = call.arch.syscall_args(call.nr);
+    for (i, kind) in kinds.iter().enumerate() {
+        let fd = call.raw_args[i] as i32;
+        if *kind != ArgKind::Fd || fd < 0 {
+            continue;
+        }
+        if let Some(target) = fds.lookup(call.pid, fd) {
+            call.args[i] = format!("{}<{}>", fd, target);
+        }
+    }
+}
+
+/// Print the struct arguments of a call field by field once they are there.
+/// Those the kernel fills in are only read if the call succeeded.
+pub fn decode_structs(call: &mut SyscallEvent, at_exit: bool) {
+    if at_exit && call.errno().is_some() {
+        return;
+    }
+    for (idx, fields) in structs::decode(call.tid, call.arch, call.name, &call.raw_args, at_exit) {
+        call.args[idx] = fields;
     }
 }
 
-pub fn trace_print(pid: Pid, line: &Vec<String>, topt: &TraceOption) {
-    let idx = line.first().unwrap().find('(').unwrap();
-    let name = &line.first().unwrap()[..idx].to_string();
+pub fn is_traced(name: &str, topt: &TraceOption) -> bool {
     match topt {
-        TraceOption::Ignore(ref v) => {
-            if !v.contains(name) {
-                println!("[{}]: {}", pid, line.join(" = "));
+        TraceOption::Ignore(ref v) => !v.iter().any(|s| s == name),
+        TraceOption::Trace(ref v) => v.iter().any(|s| s == name),
+    }
+}
+
+/// Whether the filters the user set up let `call` through.
+pub fn is_reported(call: &SyscallEvent, config: &TraceConfig) -> bool {
+    is_traced(call.name, &config.topt) && config.status.matches(call.errno().is_some())
+}
+
+/// How many arguments of a call are known at entry: those before the first
+/// one the kernel fills in.
+fn args_at_entry(call: &SyscallEvent) -> usize {
+    let data = buffers::data_arg(call.name).filter(|arg| arg.at_exit).map(|arg| arg.buf);
+    data.into_iter()
+        .chain(structs::filled_at_exit(call.name))
+        .chain(std::iter::once(call.args.len()))
+        .min()
+        .unwrap_or(0)
+}
+
+/// Start the text line of a call as it is entered, so calls which block for
+/// long show up while they do. Calls that may yet be filtered out by their
+/// result are printed whole when they return.
+pub fn trace_print_entry(call: &mut SyscallEvent, config: &mut TraceConfig, start: SystemTime)
+                         -> io::Result<()> {
+    if config.format != Format::Text || config.status != Status::All
+        || !is_traced(call.name, &config.topt) {
+        return Ok(());
+    }
+    let shown = args_at_entry(call);
+    let mut text = call.entry_text(shown);
+    if let Some(timestamps) = config.timestamps {
+        text = format!("{} {}", timestamps.format(call.entered_at, start), text);
+    }
+    config.output.start_line(call.tid, &text)?;
+    call.started = Some(shown);
+    Ok(())
+}
+
+/// Print a finished call if the user asked for it, decorated as configured.
+/// A line started at entry is finished, or if other lines came in between,
+/// continued on a `<... name resumed>` line.
+pub fn trace_print(call: &SyscallEvent, config: &mut TraceConfig, start: SystemTime) -> io::Result<()> {
+    if !is_reported(call, config) {
+        return Ok(());
+    }
+    match config.format {
+        Format::Text => print_text(call, config, start),
+        Format::Json => config.output.write_raw(call.tid, &call.to_json()),
+    }
+}
+
+/// The text line of a finished call, its stack and its hex dump, if any.
+fn print_text(call: &SyscallEvent, config: &mut TraceConfig, start: SystemTime) -> io::Result<()> {
+    let duration = match (config.syscall_times, call.duration) {
+        (true, Some(d)) => format!(" {}", timestamps::duration(d)),
+        _ => String::new(),
+    };
+    let line = match call.started {
+        Some(shown) => {
+            let rest = format!("{}{}", call.exit_text(shown), duration);
+            if config.output.finish_line(call.tid, &rest)? {
+                None
+            } else {
+                Some((SystemTime::now(), format!("<... {} resumed>{}", call.name, rest)))
             }
         },
-        TraceOption::Trace(ref v) => {
-            if v.contains(name) {
-                println!("[{}]: {}", pid, line.join(" = "));
-            }
+        None => Some((call.entered_at, format!("{}{}", call.to_text(), duration))),
+    };
+    if let Some((at, line)) = line {
+        let line = match config.timestamps {
+            Some(timestamps) => format!("{} {}", timestamps.format(at, start), line),
+            None => line,
+        };
+        config.output.write_line(call.tid, &line)?;
+    }
+    for frame in call.stack.iter().flatten() {
+        config.output.write_line(call.tid, &frame.to_text())?;
+    }
+    for line in call.data.iter().flat_map(|data| buffers::hexdump(data, call.data_truncated)) {
+        config.output.write_line(call.tid, &line)?;
+    }
+    Ok(())
+}
+
+/// Print a line about a process rather than one of its calls: a signal sent
+/// to it, or how it ended. JSON output gets an object of `fields` instead.
+fn print_note(config: &mut TraceConfig, tid: Pid, start: SystemTime, text: &str,
+              fields: &[(&str, String)]) -> io::Result<()> {
+    match config.format {
+        Format::Text => {
+            let line = match config.timestamps {
+                Some(timestamps) => format!("{} {}", timestamps.format(SystemTime::now(), start), text),
+                None => text.to_string(),
+            };
+            config.output.write_line(tid, &line)
         },
+        Format::Json => {
+            let mut object = vec![("tid", tid.to_string())];
+            object.extend_from_slice(fields);
+            config.output.write_raw(tid, &json::object(&object))
+        },
+    }
+}
+
+/// Where a traced thread is in its current system call.
+struct Hook {
+    prehook: bool,
+    /// The call being made, `None` for a thread we attached to in the middle
+    /// of one.
+    call: Option<SyscallEvent>,
+    entered: Instant,
+    /// The thread group the thread belongs to.
+    pid: Pid,
+    /// The fault to report at exit of a call we stopped from running.
+    fault: Option<Fault>,
+}
+
+impl Hook {
+    fn new(prehook: bool, tid: Pid) -> Hook {
+        Hook {
+            prehook,
+            call: None,
+            entered: Instant::now(),
+            pid: util::thread_group(tid),
+            fault: None,
+        }
+    }
+}
+
+/// Check the call `regs` is entering against the injection specs and, if one
+/// picks it, turn it into a call to syscall number -1 so the kernel skips it
+/// with `ENOSYS`. The fault replaces that result at the exit stop.
+fn inject_entry(pid: Pid, regs: &mut Regs, name: &str,
+                injections: &mut [Injection]) -> nix::Result<Option<Fault>> {
+    // Every spec sees every call, so their counters stay independent.
+    let fault = injections.iter_mut().fold(None, |fault, i| i.check(name).or(fault));
+    if fault.is_some() {
+        regs.skip_call();
+        regs.set(pid)?;
+    }
+    Ok(fault)
+}
+
+fn inject_exit(pid: Pid, regs: &mut Regs, fault: Fault) -> nix::Result<()> {
+    regs.set_retval(fault.retval());
+    regs.set(pid)
+}
+
+/// Name of a `PTRACE_EVENT_*` value for logging. `PTRACE_EVENT_STOP` is not in
+/// nix, it is reported for stops requested with `PTRACE_INTERRUPT`.
+fn ptrace_event_name(event: i32) -> &'static str {
+    match event {
+        1..=7 => DEBUG_PTRACE_EVENT[event as usize - 1],
+        PTRACE_EVENT_STOP => "PTRACE_EVENT_STOP",
+        _ => "PTRACE_EVENT_UNKNOWN",
+    }
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_interrupt(_: c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Catch Ctrl-C and termination requests so we can detach from the tracees
+/// instead of taking them down with us. The handler is installed without
+/// `SA_RESTART` so a blocked `waitpid` returns with `EINTR`.
+fn catch_interrupts() -> nix::Result<()> {
+    let action = signal::SigAction::new(signal::SigHandler::Handler(handle_interrupt),
+                                        signal::SaFlags::empty(),
+                                        signal::SigSet::empty());
+    for sig in &[signal::Signal::SIGINT, signal::Signal::SIGTERM, signal::Signal::SIGHUP] {
+        unsafe { signal::sigaction(*sig, &action)?; }
+    }
+    Ok(())
+}
+
+/// The signal a tracee in `status` is about to be delivered. Group-stops look
+/// like signal-delivery stops but have no siginfo.
+fn pending_signal(pid: Pid, status: wait::WaitStatus) -> i32 {
+    match status {
+        wait::WaitStatus::Stopped(_, signal) if ptrace::getsiginfo(pid).is_ok() => signal as i32,
+        _ => 0,
+    }
+}
+
+/// Stop every tracee and detach from it, leaving it running as if it had never
+/// been traced.
+fn detach_all(live_process: &HashSet<Pid>, proc_hook: &mut HashMap<Pid, Hook>) -> nix::Result<()> {
+    for &pid in live_process {
+        if util::ptrace_interrupt(pid).is_err() {
+            // Already gone.
+            continue;
+        }
+        // The interrupt may be preceded by a stop that was already pending.
+        let mut status = wait::waitpid(pid, Some(wait::WaitPidFlag::__WALL))?;
+        // A call we inject a fault into is seen to its exit first, or it
+        // returns ENOSYS instead.
+        if let Some(fault) = proc_hook.get_mut(&pid).and_then(|hook| hook.fault.take()) {
+            loop {
+                match status {
+                    wait::WaitStatus::PtraceSyscall(_) => {
+                        inject_exit(pid, &mut Regs::get(pid)?, fault)?;
+                        break;
+                    },
+                    wait::WaitStatus::Exited(..) | wait::WaitStatus::Signaled(..) => break,
+                    _ => util::ptrace_syscall(pid, pending_signal(pid, status))?,
+                }
+                status = wait::waitpid(pid, Some(wait::WaitPidFlag::__WALL))?;
+            }
+        }
+        match status {
+            wait::WaitStatus::Exited(..) | wait::WaitStatus::Signaled(..) => continue,
+            _ => {},
+        }
+        info!("[{}] Detaching", pid);
+        util::ptrace_detach(pid, pending_signal(pid, status))?;
+    }
+    Ok(())
+}
+
+/// Kill every tracee and wait for them to be gone, for when we stop tracing a
+/// command we started.
+fn kill_all(live_process: &mut HashSet<Pid>) -> nix::Result<()> {
+    for &pid in live_process.iter() {
+        // Gone already if this fails.
+        let _ = signal::kill(pid, signal::Signal::SIGKILL);
+    }
+    while !live_process.is_empty() {
+        match wait::waitpid(Pid::from_raw(-1), Some(wait::WaitPidFlag::__WALL))? {
+            wait::WaitStatus::Exited(pid, _) | wait::WaitStatus::Signaled(pid, ..) => {
+                live_process.remove(&pid);
+            },
+            // Such as the stop at PTRACE_EVENT_EXIT on the way out.
+            status => if let Some(pid) = status.pid() {
+                let _ = util::ptrace_cont(pid, 0);
+            },
+        }
     }
+    Ok(())
+}
+
+/// How the process we traced ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitStatus {
+    Exited(i32),
+    /// Killed by a signal, and whether it dumped core.
+    Killed(signal::Signal, bool),
+}
+
+/// Whether we need to stop at calls to `name`: to report them, inject faults
+/// into them or keep track of what they do.
+fn needs_stop(name: &str, config: &TraceConfig) -> bool {
+    is_traced(name, &config.topt)
+        || config.injections.iter().any(|injection| injection.selects(name))
+        || (config.decode_fds && fds::tracks(name))
+        || (config.tree && Exec::is_exec(name))
 }
 
-pub fn trace(prog: &CString, args: &[CString], topt: TraceOption) -> nix::Result<()> {
+/// Fork and exec `prog`, started as `command` says, tracing it and
+/// everything it spawns and passing what they do to `on_event`. Returns how
+/// it ended, once all of them did.
+///
+/// Fails with `EINVAL` if asked to use a seccomp filter without following
+/// forks: the processes we let go would keep the filter and fail the calls
+/// it picks with `ENOSYS`.
+pub fn trace(prog: &CString, args: &[CString], command: &Command, config: TraceConfig,
+             on_event: &mut dyn FnMut(Event)) -> nix::Result<Option<ExitStatus>> {
+    if config.seccomp_bpf && !config.follow_forks {
+        return Err(nix::Error::invalid_argument());
+    }
+    let launch = command.prepare(prog, args)?;
+    let filter = if config.seccomp_bpf {
+        Some(seccomp::filter(|name| needs_stop(name, &config)))
+    } else {
+        None
+    };
     match fork()? {
         ForkResult::Parent { child, .. } => {
-            wait::waitpid(child, None)?;
-            util::ptrace_set_options(child)?;
-            ptrace::syscall(child)?;
-
-            let mut live_process = HashSet::new();
-            live_process.insert(child);
-            let mut proc_hook = HashMap::new();
-            proc_hook.insert(child, (true, Vec::<String>::new()));
+            // Seized rather than attached with PTRACE_TRACEME, so that we can
+            // tell group-stops from signals and leave the command stopped
+            // when it is stopped.
+            match wait::waitpid(child, Some(wait::WaitPidFlag::WSTOPPED))? {
+                // It could not be set up, and said why.
+                wait::WaitStatus::Exited(_, code) => return Ok(Some(ExitStatus::Exited(code))),
+                wait::WaitStatus::Signaled(_, signal, core_dumped) => {
+                    return Ok(Some(ExitStatus::Killed(signal, core_dumped)))
+                },
+                _ => (),
+            }
+            util::ptrace_seize(child, config.follow_forks, config.seccomp_bpf)?;
+            signal::kill(child, signal::Signal::SIGCONT)?;
+            // The SIGCONT is ours, not the command's.
             loop {
-                let actual_pid = match wait::wait()? {
-                    wait::WaitStatus::Exited(pid, code) => {
-                        info!("[{}] Process exit normally with code {}", pid, code);
-                        let (_, line) = proc_hook.get_mut(&pid).unwrap();
-                        line.push("Process finished!".to_string());
-
-                        trace_print(pid, &line, &topt);
-                        line.clear();
-                        live_process.remove(&pid);
-                        if live_process.len() == 0 { break }
-                        else { continue }
-                    },
-                    wait::WaitStatus::PtraceEvent(pid, signal, c) => {
-                        info!{"[{}] Process {} by {:?}", pid, DEBUG_PTRACE_EVENT[c as usize], signal};
-                        pid
-                    },
-                    wait::WaitStatus::PtraceSyscall(pid) => {
-                        // info!("[{}] Syscall", pid);
-                        if live_process.get(&pid).is_none() {
-                            live_process.insert(pid);
+                match wait::waitpid(child, Some(wait::WaitPidFlag::__WALL))? {
+                    wait::WaitStatus::Stopped(_, signal::Signal::SIGCONT) => break,
+                    _ => util::ptrace_cont(child, 0)?,
+                }
+            }
+            if config.seccomp_bpf {
+                util::ptrace_cont(child, 0)?;
+            } else {
+                util::ptrace_syscall(child, 0)?;
+            }
+            trace_loop(vec![child], false, config, on_event)
+        },
+        ForkResult::Child => {
+            if let Err((what, e)) = launch.apply() {
+                exit_child(what, e);
+            }
+            if let Err(e) = signal::raise(signal::Signal::SIGSTOP) {
+                exit_child(b"cannot stop for the tracer", e);
+            }
+            // Only once we stopped, so the tracer can ask to hear of the
+            // calls the filter picks before there are any.
+            if let Some(filter) = filter {
+                if let Err(e) = seccomp::install(&filter) {
+                    exit_child(b"cannot install the seccomp filter", e);
+                }
+            }
+            exit_child(launch.prog().to_bytes(), launch.exec())
+        },
+    }
+}
+
+/// End the child forked to run the command, which failed to at `what`. It
+/// must never return into the code of whoever called us, and writes why
+/// with nothing but `write`, as is safe in the child of a threaded process.
+fn exit_child(what: &[u8], error: nix::Error) -> ! {
+    let message = match error {
+        nix::Error::Sys(errno) => errno.desc(),
+        _ => "Invalid argument",
+    };
+    for part in &[b"stracer: ", what, b": ", message.as_bytes(), b"\n"] {
+        unsafe {
+            libc::write(libc::STDERR_FILENO, part.as_ptr() as *const libc::c_void, part.len());
+        }
+    }
+    unsafe { libc::_exit(127) }
+}
+
+/// Trace the already running process `pid` and all of its threads. The
+/// process keeps running after we detach on Ctrl-C.
+///
+/// Fails with `EINVAL` if asked to use a seccomp filter, which can only be
+/// installed by a command we start.
+pub fn attach(pid: Pid, config: TraceConfig, on_event: &mut dyn FnMut(Event)) -> nix::Result<()> {
+    if config.seccomp_bpf {
+        return Err(nix::Error::invalid_argument());
+    }
+    catch_interrupts()?;
+    let mut tasks = Vec::new();
+    // Threads may be spawned while we attach to their siblings, go around
+    // until the task list stops growing.
+    loop {
+        let new_tasks = util::process_tasks(pid)
+            .map_err(|_| nix::Error::Sys(nix::errno::Errno::ESRCH))?
+            .into_iter()
+            .filter(|tid| !tasks.contains(tid))
+            .collect::<Vec<Pid>>();
+        if new_tasks.is_empty() { break }
+        for tid in new_tasks {
+            info!("[{}] Attaching", tid);
+            util::ptrace_seize(tid, config.follow_forks, false)?;
+            util::ptrace_interrupt(tid)?;
+            tasks.push(tid);
+        }
+    }
+    trace_loop(tasks, true, config, on_event).map(|_| ())
+}
+
+/// Trace `tracees` until they and everything they spawn are gone, or until
+/// we are interrupted. Returns how the first of them ended, if it did.
+///
+/// If the trace can't be written we stop: tracees we `attached` to are let
+/// go, commands we started are killed. Nobody reading the trace any more, as
+/// when it is piped into `head`, is not an error.
+fn trace_loop(tracees: Vec<Pid>, attached: bool, mut config: TraceConfig,
+              on_event: &mut dyn FnMut(Event)) -> nix::Result<Option<ExitStatus>> {
+    let main_process = util::thread_group(tracees[0]);
+    let mut main_status = None;
+    let start = SystemTime::now();
+    let mut live_process = tracees.into_iter().collect::<HashSet<Pid>>();
+    // Tasks of which we saw either the end or the fork event announcing
+    // them, but not both yet. A new task may run and exit before its parent
+    // reports the fork.
+    let mut unpaired = live_process.clone();
+    let mut proc_hook: HashMap<Pid, Hook> = HashMap::new();
+    let mut summary = Summary::new();
+    let mut fds = FdTable::new();
+    let mut tree = ProcessTree::new();
+    let mut unwinder = Unwinder::new();
+    // The processes we were asked to trace, all there is unless we follow
+    // forks.
+    let initial = live_process.iter().map(|&tid| util::thread_group(tid)).collect::<HashSet<Pid>>();
+    if config.tree {
+        let mut processes = initial.iter().cloned().collect::<Vec<Pid>>();
+        processes.sort_by_key(|&pid| i32::from(pid));
+        processes.dedup();
+        for pid in processes {
+            tree.spawned(pid, None, start);
+        }
+    }
+    // Writing the trace failed if this is an error, we stop once the
+    // tracee that stopped is on its way again.
+    let mut written = Ok(());
+    while written.is_ok() {
+        let status = match wait::waitpid(Pid::from_raw(-1), Some(wait::WaitPidFlag::__WALL)) {
+            Err(nix::Error::Sys(nix::errno::Errno::EINTR)) => {
+                if INTERRUPTED.load(Ordering::SeqCst) {
+                    detach_all(&live_process, &mut proc_hook)?;
+                    break
+                }
+                continue
+            },
+            status => status?,
+        };
+        // Under our seccomp filter the stop for a call it picks stands in
+        // for the syscall-enter stop, which we don't ask for.
+        let status = match status {
+            wait::WaitStatus::PtraceEvent(pid, _, c) if c == ptrace::Event::PTRACE_EVENT_SECCOMP as i32 => {
+                wait::WaitStatus::PtraceSyscall(pid)
+            },
+            status => status,
+        };
+        // The task to resume, and the signal to deliver to it, if any.
+        let (actual_pid, deliver) = match status {
+            wait::WaitStatus::Exited(pid, code) => {
+                info!("[{}] Process exit normally with code {}", pid, code);
+                // The last call, exit or exit_group, never returns.
+                if let Some(call) = proc_hook.remove(&pid).and_then(|hook| hook.call) {
+                    if config.summary {
+                        if is_reported(&call, &config) {
+                            summary.record(call.name, Duration::from_secs(0), false);
+                        }
+                    } else {
+                        written = written.and(trace_print(&call, &mut config, start));
+                    }
+                    if is_reported(&call, &config) {
+                        on_event(Event::Syscall(call));
+                    }
+                }
+                if !config.summary {
+                    let text = format!("+++ exited with {} +++", code);
+                    written = written.and(print_note(&mut config, pid, start, &text,
+                                                     &[("exited", code.to_string())]));
+                }
+                live_process.remove(&pid);
+                if !unpaired.remove(&pid) {
+                    unpaired.insert(pid);
+                }
+                fds.forget(pid);
+                config.output.forget(pid);
+                tree.exited(pid, code, SystemTime::now());
+                if pid == main_process {
+                    main_status = Some(ExitStatus::Exited(code));
+                }
+                on_event(Event::Process(ProcessEvent::Exited { pid, code }));
+                if live_process.is_empty() { break }
+                else { continue }
+            },
+            wait::WaitStatus::Signaled(pid, signal, core_dumped) => {
+                info!("[{}] Process killed by {:?}", pid, signal);
+                // The call it was in, if any, never returns.
+                proc_hook.remove(&pid);
+                if !config.summary {
+                    let name = flags::signal_name(signal as i32);
+                    let core = if core_dumped { " (core dumped)" } else { "" };
+                    let text = format!("+++ killed by {}{} +++", name, core);
+                    written = written.and(print_note(&mut config, pid, start, &text,
+                                                     &[("killed", json::string(&name)),
+                                                       ("core_dumped", core_dumped.to_string())]));
+                }
+                live_process.remove(&pid);
+                if !unpaired.remove(&pid) {
+                    unpaired.insert(pid);
+                }
+                fds.forget(pid);
+                config.output.forget(pid);
+                tree.killed(pid, flags::signal_name(signal as i32), core_dumped, SystemTime::now());
+                if pid == main_process {
+                    main_status = Some(ExitStatus::Killed(signal, core_dumped));
+                }
+                on_event(Event::Process(ProcessEvent::Killed { pid, signal, core_dumped }));
+                if live_process.is_empty() { break }
+                else { continue }
+            },
+            wait::WaitStatus::Stopped(pid, _) | wait::WaitStatus::PtraceEvent(pid, _, PTRACE_EVENT_STOP)
+                if !config.follow_forks && !live_process.contains(&pid)
+                    && !initial.contains(&util::thread_group(pid)) => {
+                // A process started with a plain clone, which reports like a
+                // new thread does. Let it go at its first stop.
+                info!("[{}] Detaching new process", pid);
+                match ptrace::detach(pid) {
+                    Err(nix::Error::Sys(nix::errno::Errno::ESRCH)) => (),
+                    result => result?,
+                }
+                continue
+            },
+            wait::WaitStatus::PtraceEvent(pid, signal, PTRACE_EVENT_STOP)
+                if signals::is_stopping(signal as i32) && live_process.contains(&pid) => {
+                // A seized tracee in a group-stop, it stays stopped until
+                // something continues it.
+                if !config.summary {
+                    let name = flags::signal_name(signal as i32);
+                    let text = format!("--- stopped by {} ---", name);
+                    written = written.and(print_note(&mut config, pid, start, &text,
+                                                     &[("stopped", json::string(&name))]));
+                }
+                util::ptrace_listen(pid)?;
+                continue
+            },
+            wait::WaitStatus::PtraceEvent(pid, signal, c) => {
+                info!{"[{}] Process {} by {:?}", pid, ptrace_event_name(c), signal};
+                let forked = [ptrace::Event::PTRACE_EVENT_FORK, ptrace::Event::PTRACE_EVENT_VFORK,
+                              ptrace::Event::PTRACE_EVENT_CLONE];
+                if forked.iter().any(|&e| e as i32 == c) {
+                    let child = Pid::from_raw(ptrace::getevent(pid)? as i32);
+                    let (parent_group, child_group) = (util::thread_group(pid), util::thread_group(child));
+                    let thread = parent_group == child_group;
+                    // Counted as live from now on, a child which only makes
+                    // calls our seccomp filter lets through never stops for
+                    // them.
+                    if (thread || config.follow_forks) && !unpaired.remove(&child) {
+                        unpaired.insert(child);
+                        live_process.insert(child);
+                    }
+                    if config.decode_fds {
+                        fds.inherit(parent_group, child_group);
+                    }
+                    if config.tree && !thread {
+                        tree.spawned(child_group, Some(parent_group), SystemTime::now());
+                    }
+                    on_event(Event::Process(ProcessEvent::Spawned { pid: child, parent: pid, thread }));
+                }
+                if c == ptrace::Event::PTRACE_EVENT_EXEC as i32 {
+                    tree.exec_done(pid);
+                    let argv = util::process_cmdline(pid).unwrap_or_default();
+                    on_event(Event::Process(ProcessEvent::Exec { pid, argv }));
+                }
+                (pid, 0)
+            },
+            wait::WaitStatus::PtraceSyscall(pid) => {
+                // info!("[{}] Syscall", pid);
+                live_process.insert(pid);
+                let mut regs = match Regs::get(pid) {
+                    Ok(regs) => regs,
+                    // Killed since it stopped, we hear about that next.
+                    Err(nix::Error::Sys(nix::errno::Errno::ESRCH)) => continue,
+                    Err(e) => return Err(e),
+                };
+                // A process we attached to may be stopped on its way out of a
+                // system call, its first stop then has no entry to pair with.
+                let hook = proc_hook.entry(pid)
+                    .or_insert_with(|| Hook::new(regs.is_entry(), pid));
+                if hook.prehook {
+                    let mut call = trace_prehook(&regs, pid, hook.pid, !config.summary);
+                    if config.stack_trace && is_traced(call.name, &config.topt) {
+                        call.stack = Some(unwinder.stack(pid, &regs));
+                    }
+                    if !config.summary {
+                        decode_data(&mut call, false, &config);
+                        decode_structs(&mut call, false);
+                        if config.decode_fds {
+                            decode_fds(&mut call, &mut fds);
                         }
-                        if proc_hook.get(&pid).is_none() {
-                            proc_hook.insert(pid, (true, Vec::<String>::new()));
+                        written = written.and(trace_print_entry(&mut call, &mut config, start));
+                    }
+                    if config.tree {
+                        if let Some(exec) = Exec::read(&call) {
+                            tree.exec_entered(hook.pid, exec);
                         }
-                        let regs = util::get_regs(pid);
-                        let (prehook, line) = proc_hook.get_mut(&pid).unwrap();
-                        if *prehook {
-                            line.push(trace_prehook(&regs, pid));
-                        } else {
-                            line.push(trace_posthook(&regs));
-                        
-                            trace_print(pid, &line, &topt);
-                            line.clear();
+                    }
+                    hook.fault = inject_entry(pid, &mut regs, call.name, &mut config.injections)?;
+                    hook.entered = Instant::now();
+                    hook.call = Some(call);
+                } else if let Some(mut call) = hook.call.take() {
+                    if let Some(fault) = hook.fault.take() {
+                        inject_exit(pid, &mut regs, fault)?;
+                        call.injected = true;
+                    }
+                    trace_posthook(&regs, &mut call, hook.entered.elapsed());
+                    if config.decode_fds {
+                        fds.update(&call);
+                    }
+                    if config.summary {
+                        if is_reported(&call, &config) {
+                            summary.record(call.name, call.duration.unwrap_or_default(),
+                                           call.errno().is_some());
                         }
-                        *prehook = !*prehook;
-                        // proc_hook.insert(pid, prehook);
-                        pid
-                    },
-                    wait::WaitStatus::Signaled(pid, signal, b) => {
-                        let core_dumped = if b { "(core dumped)" } else { "" };
-                        info!("[{}] Signal {:?} received {}", pid, signal, core_dumped);
-                        break
-                    },
-                    wait::WaitStatus::Stopped(pid, signal) => {
-                        info!("[{}] Process stopped by {:?}", pid, signal);
-                        pid
-                        // break
-                    },
-                    wait::WaitStatus::Continued(pid) => {
-                        info!("[{}] Process continued", pid);
-                        break
+                    } else {
+                        decode_data(&mut call, true, &config);
+                        decode_structs(&mut call, true);
+                        written = written.and(trace_print(&call, &mut config, start));
+                    }
+                    if is_reported(&call, &config) {
+                        on_event(Event::Syscall(call));
+                    }
+                }
+                hook.prehook = !hook.prehook;
+                (pid, 0)
+            },
+            wait::WaitStatus::Stopped(pid, signal) if !live_process.contains(&pid) => {
+                // New tasks start with a SIGSTOP of their own, meant for us.
+                info!("[{}] New task stopped by {:?}", pid, signal);
+                live_process.insert(pid);
+                (pid, 0)
+            },
+            wait::WaitStatus::Stopped(pid, signal) => {
+                info!("[{}] Process stopped by {:?}", pid, signal);
+                let name = flags::signal_name(signal as i32);
+                match ptrace::getsiginfo(pid) {
+                    // A signal on its way to the tracee, pass it on.
+                    Ok(info) => {
+                        let siginfo = signals::format_siginfo(&info);
+                        if !config.summary {
+                            written = written.and(print_note(&mut config, pid, start,
+                                                             &format!("--- {} {} ---", name, siginfo),
+                                                             &[("signal", json::string(&name)),
+                                                               ("siginfo", json::string(&siginfo))]));
+                        }
+                        on_event(Event::Signal(SignalEvent {
+                            pid: util::thread_group(pid),
+                            tid: pid,
+                            signal,
+                            code: info.si_code,
+                            info: siginfo,
+                        }));
+                        (pid, signal as i32)
                     },
-                    wait::WaitStatus::StillAlive => {
-                        info!("Still alive");
-                        break
+                    // A group-stop, which a tracee we did not seize can only
+                    // be resumed from.
+                    Err(_) => {
+                        if !config.summary {
+                            written = written.and(print_note(&mut config, pid, start,
+                                                             &format!("--- stopped by {} ---", name),
+                                                             &[("stopped", json::string(&name))]));
+                        }
+                        (pid, 0)
                     },
-                };
-                ptrace::syscall(actual_pid)?;
-            }
-        },
-        ForkResult::Child => {
-            ptrace::traceme()?;
-            signal::raise(signal::Signal::SIGSTOP)?;
-            execvp(prog, args)?;
+                }
+            },
+            wait::WaitStatus::Continued(pid) => {
+                info!("[{}] Process continued", pid);
+                break
+            },
+            wait::WaitStatus::StillAlive => {
+                info!("Still alive");
+                break
+            },
+        };
+        // With a seccomp filter we only need the exit of calls it stopped
+        // the tracee for.
+        let in_call = proc_hook.get(&actual_pid).is_some_and(|hook| !hook.prehook);
+        let resumed = if config.seccomp_bpf && !in_call {
+            util::ptrace_cont(actual_pid, deliver)
+        } else {
+            util::ptrace_syscall(actual_pid, deliver)
+        };
+        // The tracee may have been killed since it stopped.
+        match resumed {
+            Err(nix::Error::Sys(nix::errno::Errno::ESRCH)) => (),
+            result => result?,
+        }
+    }
+    if let Err(e) = written {
+        if attached {
+            detach_all(&live_process, &mut proc_hook)?;
+        } else {
+            kill_all(&mut live_process)?;
+        }
+        return output_result(Err(e), main_status);
+    }
+    let mut report = Vec::new();
+    if config.summary {
+        report.push(summary.report());
+    }
+    if config.tree {
+        report.push(match config.format {
+            Format::Text => tree.report(start),
+            Format::Json => tree.to_json(start).iter().map(|line| format!("{}\n", line)).collect(),
+        });
+    }
+    let written = if report.is_empty() {
+        config.output.interrupt_all()
+    } else {
+        config.output.write_report(&report.join("\n"))
+    };
+    output_result(written, main_status)
+}
+
+/// How the trace went given whether it could be written: a broken pipe just
+/// means nobody wanted the rest.
+fn output_result(written: io::Result<()>, main_status: Option<ExitStatus>)
+                 -> nix::Result<Option<ExitStatus>> {
+    match written {
+        Err(ref e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            Err(nix::Error::Sys(e.raw_os_error().map_or(nix::errno::Errno::EIO, nix::errno::Errno::from_i32)))
         },
+        _ => Ok(main_status),
     }
-    Ok(())
-}
\ No newline at end of file
+}
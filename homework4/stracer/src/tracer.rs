@@ -0,0 +1,205 @@
+//! The entry point of the library: configure a `Tracer`, then trace a command
+//! or a running process, receiving each `Event` as it happens.
+
+use crate::command::{Command, EnvChange, User};
+use crate::events::Event;
+use crate::filter::{self, Status};
+use crate::inject::Injection;
+use crate::output::{Format, Output};
+use crate::trace::{self, ExitStatus};
+use crate::{TraceConfig, TraceOption};
+use nix::unistd::Pid;
+use std::ffi::CString;
+use std::path::PathBuf;
+
+/// Builds up a trace. By default every call of the process and of all the
+/// processes it starts is reported, and nothing is printed.
+#[derive(Debug)]
+pub struct Tracer {
+    config: TraceConfig,
+    command: Command,
+}
+
+impl Default for Tracer {
+    fn default() -> Tracer {
+        Tracer::new()
+    }
+}
+
+impl Tracer {
+    pub fn new() -> Tracer {
+        Tracer {
+            config: TraceConfig {
+                topt: TraceOption::Ignore(Vec::new()),
+                status: Status::All,
+                summary: false,
+                output: Output::discard(),
+                format: Format::Text,
+                timestamps: None,
+                syscall_times: false,
+                injections: Vec::new(),
+                string_limit: 32,
+                hex_dump: Vec::new(),
+                decode_fds: false,
+                tree: false,
+                follow_forks: true,
+                seccomp_bpf: false,
+                stack_trace: false,
+            },
+            command: Command::default(),
+        }
+    }
+
+    /// Narrow down the calls reported with a filter expression, as given to
+    /// `-e`: `trace=SET` or `status=failed|successful`.
+    pub fn filter(mut self, expr: &str) -> Result<Tracer, String> {
+        filter::apply(expr, &mut self.config.topt, &mut self.config.status)?;
+        Ok(self)
+    }
+
+    /// Also print the trace, as the `stracer` command does.
+    pub fn print_to(mut self, output: Output, format: Format) -> Tracer {
+        self.config.output = output;
+        self.config.format = format;
+        self
+    }
+
+    /// Follow the processes the traced one forks, which is the default.
+    pub fn follow_forks(mut self, follow: bool) -> Tracer {
+        self.config.follow_forks = follow;
+        self
+    }
+
+    /// Stop commands only for the calls reported, see `--seccomp_bpf`. Only
+    /// for `spawn` while following forks, otherwise tracing fails with
+    /// `EINVAL`.
+    pub fn seccomp_bpf(mut self, seccomp_bpf: bool) -> Tracer {
+        self.config.seccomp_bpf = seccomp_bpf;
+        self
+    }
+
+    /// Capture the call stack of the reported calls as they are entered.
+    pub fn stack_trace(mut self, stack_trace: bool) -> Tracer {
+        self.config.stack_trace = stack_trace;
+        self
+    }
+
+    /// Show what file descriptors refer to in the arguments of calls.
+    pub fn decode_fds(mut self, decode: bool) -> Tracer {
+        self.config.decode_fds = decode;
+        self
+    }
+
+    /// Show at most `limit` bytes of the data read or written.
+    pub fn string_limit(mut self, limit: usize) -> Tracer {
+        self.config.string_limit = limit;
+        self
+    }
+
+    pub fn inject(mut self, injection: Injection) -> Tracer {
+        self.config.injections.push(injection);
+        self
+    }
+
+    /// Run commands as `user`.
+    pub fn user(mut self, user: User) -> Tracer {
+        self.command.user = Some(user);
+        self
+    }
+
+    /// Change the environment of commands.
+    pub fn env(mut self, change: EnvChange) -> Tracer {
+        self.command.env.push(change);
+        self
+    }
+
+    /// Run commands in `directory`.
+    pub fn directory(mut self, directory: PathBuf) -> Tracer {
+        self.command.directory = Some(directory);
+        self
+    }
+
+    /// Run `prog`, looked up in `PATH`, with `args` starting with its name,
+    /// and trace it until it and everything it started are gone. Returns how
+    /// it ended.
+    pub fn spawn<F: FnMut(Event)>(self, prog: &str, args: &[&str], mut on_event: F)
+                                  -> nix::Result<Option<ExitStatus>> {
+        let c_string = |s: &str| CString::new(s).map_err(|_| nix::Error::invalid_argument());
+        let prog = c_string(prog)?;
+        let args = args.iter().map(|arg| c_string(arg)).collect::<nix::Result<Vec<CString>>>()?;
+        trace::trace(&prog, &args, &self.command, self.config, &mut on_event)
+    }
+
+    /// Trace the running process `pid` and its threads, until it ends or
+    /// we get SIGINT, SIGTERM or SIGHUP.
+    pub fn attach<F: FnMut(Event)>(self, pid: Pid, mut on_event: F) -> nix::Result<()> {
+        trace::attach(pid, self.config, &mut on_event)
+    }
+}
+
+#[test]
+fn spawn_test() {
+    use crate::events::ProcessEvent;
+    let mut names = Vec::new();
+    let mut exited = None;
+    let status = Tracer::new()
+        .filter("trace=execve,exit_group").unwrap()
+        .spawn("/bin/sh", &["sh", "-c", "exit 3"], |event| match event {
+            Event::Syscall(call) => names.push(call.name),
+            Event::Process(ProcessEvent::Exited { code, .. }) => exited = Some(code),
+            _ => (),
+        })
+        .unwrap();
+    assert_eq!(status, Some(ExitStatus::Exited(3)));
+    assert_eq!(exited, Some(3));
+    assert_eq!(names, vec!["execve", "exit_group"]);
+}
+
+#[test]
+fn seccomp_bpf_test() {
+    let einval = Err(nix::Error::Sys(nix::errno::Errno::EINVAL));
+    let status = Tracer::new()
+        .seccomp_bpf(true)
+        .follow_forks(false)
+        .spawn("/bin/true", &["true"], |_| ());
+    assert_eq!(status, einval);
+    let attached = Tracer::new().seccomp_bpf(true).attach(nix::unistd::getpid(), |_| ());
+    assert_eq!(attached, einval.map(|_| ()));
+}
+
+#[test]
+fn spawn_failure_test() {
+    // The child tells why and exits, it never returns here.
+    let status = Tracer::new()
+        .directory(PathBuf::from("/nonexistent"))
+        .spawn("true", &["true"], |_| ());
+    assert_eq!(status, Ok(Some(ExitStatus::Exited(127))));
+    let status = Tracer::new().spawn("/nonexistent", &["nonexistent"], |_| ());
+    assert_eq!(status, Ok(Some(ExitStatus::Exited(127))));
+}
+
+#[test]
+fn output_error_test() {
+    use std::fs::{self, OpenOptions};
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::time::Instant;
+    // Nobody reads the trace any more: the command is killed and that is it.
+    let dir = std::env::temp_dir().join(format!("stracer-tracer-{}", nix::unistd::getpid()));
+    fs::create_dir_all(&dir).unwrap();
+    let fifo = dir.join("fifo");
+    nix::unistd::mkfifo(&fifo, nix::sys::stat::Mode::S_IRWXU).unwrap();
+    let reader = OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(&fifo).unwrap();
+    let output = Output::new(Some(fifo), false).unwrap();
+    drop(reader);
+    let started = Instant::now();
+    let status = Tracer::new()
+        .print_to(output, Format::Text)
+        .spawn("sleep", &["sleep", "10"], |_| ());
+    assert_eq!(status, Ok(None));
+    assert!(started.elapsed().as_secs() < 10);
+    // Other errors are passed on.
+    let output = Output::new(Some(PathBuf::from("/dev/full")), false).unwrap();
+    let status = Tracer::new().print_to(output, Format::Text).spawn("true", &["true"], |_| ());
+    assert_eq!(status, Err(nix::Error::Sys(nix::errno::Errno::ENOSPC)));
+    fs::remove_dir_all(&dir).unwrap();
+}
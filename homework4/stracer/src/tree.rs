@@ -0,0 +1,248 @@
+//! The tree of processes a trace saw: who spawned whom, what each of them
+//! executed, and how they ended, printed at the end of the trace with
+//! `--tree`:
+//!
+//! ```text
+//! 1200 0.000000..0.051234 exited with 0
+//!   0.000412 execve("/usr/bin/make", ["make", "-j2"])
+//!     env ["PATH=/usr/bin:/bin", "HOME=/root"]
+//!   1201 0.003021..0.049876 exited with 0
+//!     0.003288 execve("/usr/bin/cc", ["cc", "-c", "main.c"])
+//!       env ["PATH=/usr/bin:/bin", "HOME=/root"]
+//! ```
+
+use crate::buffers;
+use crate::events::SyscallEvent;
+use crate::json;
+use crate::memory;
+use crate::timestamps::Timestamps;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Most `argv` and `envp` strings kept for an exec.
+const MAX_STRINGS: usize = 4096;
+
+/// A program a process executed.
+#[derive(Debug, Clone)]
+pub struct Exec {
+    at: SystemTime,
+    path: Vec<u8>,
+    argv: Vec<Vec<u8>>,
+    envp: Vec<Vec<u8>>,
+}
+
+impl Exec {
+    pub fn is_exec(name: &str) -> bool {
+        name == "execve" || name == "execveat"
+    }
+
+    /// The program `call`, an `execve` or `execveat` being entered, is
+    /// about to execute. The strings are gone once it has.
+    pub fn read(call: &SyscallEvent) -> Option<Exec> {
+        let first = match call.name {
+            "execve" => 0,
+            "execveat" => 1,
+            _ => return None,
+        };
+        let args = &call.raw_args[first..];
+        let word = call.arch.word_size();
+        let read_array = |at| match at {
+            0 => Ok(Vec::new()),
+            at => memory::read_string_array(call.tid, at, word, MAX_STRINGS),
+        };
+        Some(Exec {
+            at: call.entered_at,
+            path: memory::read_string(call.tid, args[0], memory::PATH_MAX).ok()?.0,
+            argv: read_array(args[1]).ok()?,
+            envp: read_array(args[2]).ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum End {
+    Exited(i32),
+    Killed(String, bool),
+}
+
+#[derive(Debug)]
+struct Process {
+    pid: Pid,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    started: SystemTime,
+    execs: Vec<Exec>,
+    /// The exec being entered, kept until it succeeds.
+    entering: Option<Exec>,
+    ended: Option<(SystemTime, End)>,
+}
+
+/// Every process seen during the trace, in the order they showed up. Pids
+/// may be reused, so processes are looked up by pid only while they live.
+#[derive(Debug, Default)]
+pub struct ProcessTree {
+    processes: Vec<Process>,
+    live: HashMap<Pid, usize>,
+}
+
+impl ProcessTree {
+    pub fn new() -> ProcessTree {
+        ProcessTree::default()
+    }
+
+    /// A process started, by `parent` if we trace that.
+    pub fn spawned(&mut self, pid: Pid, parent: Option<Pid>, at: SystemTime) {
+        let parent = parent.and_then(|parent| self.live.get(&parent).cloned());
+        let index = self.processes.len();
+        self.processes.push(Process {
+            pid,
+            parent,
+            children: Vec::new(),
+            started: at,
+            execs: Vec::new(),
+            entering: None,
+            ended: None,
+        });
+        if let Some(parent) = parent {
+            self.processes[parent].children.push(index);
+        }
+        self.live.insert(pid, index);
+    }
+
+    /// `pid` is entering an exec which may yet fail.
+    pub fn exec_entered(&mut self, pid: Pid, exec: Exec) {
+        if let Some(&index) = self.live.get(&pid) {
+            self.processes[index].entering = Some(exec);
+        }
+    }
+
+    /// The exec `pid` entered last succeeded.
+    pub fn exec_done(&mut self, pid: Pid) {
+        if let Some(&index) = self.live.get(&pid) {
+            let process = &mut self.processes[index];
+            if let Some(exec) = process.entering.take() {
+                process.execs.push(exec);
+            }
+        }
+    }
+
+    pub fn exited(&mut self, pid: Pid, code: i32, at: SystemTime) {
+        self.end(pid, End::Exited(code), at);
+    }
+
+    pub fn killed(&mut self, pid: Pid, signal: String, core_dumped: bool, at: SystemTime) {
+        self.end(pid, End::Killed(signal, core_dumped), at);
+    }
+
+    fn end(&mut self, pid: Pid, end: End, at: SystemTime) {
+        if let Some(index) = self.live.remove(&pid) {
+            self.processes[index].ended = Some((at, end));
+        }
+    }
+
+    /// The tree as indented text, times relative to `start`.
+    pub fn report(&self, start: SystemTime) -> String {
+        let mut out = String::new();
+        for (index, process) in self.processes.iter().enumerate() {
+            if process.parent.is_none() {
+                self.report_process(index, 0, start, &mut out);
+            }
+        }
+        out
+    }
+
+    fn report_process(&self, index: usize, depth: usize, start: SystemTime, out: &mut String) {
+        let time = |at| Timestamps::Relative.format(at, start);
+        let indent = "  ".repeat(depth);
+        let process = &self.processes[index];
+        let end = match &process.ended {
+            Some((at, End::Exited(code))) => format!("{} exited with {}", time(*at), code),
+            Some((at, End::Killed(signal, core_dumped))) => {
+                let core = if *core_dumped { " (core dumped)" } else { "" };
+                format!("{} killed by {}{}", time(*at), signal, core)
+            },
+            None => " still running".to_string(),
+        };
+        out.push_str(&format!("{}{} {}..{}\n", indent, process.pid, time(process.started), end));
+        // Execs and children in the order they happened.
+        let mut execs = process.execs.iter().peekable();
+        let mut children = process.children.iter().peekable();
+        loop {
+            let exec_first = match (execs.peek(), children.peek()) {
+                (Some(exec), Some(&&child)) => exec.at <= self.processes[child].started,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if exec_first {
+                let exec = execs.next().unwrap();
+                out.push_str(&format!("{}  {} execve({}, {})\n", indent, time(exec.at),
+                                      buffers::escape(&exec.path, false), strings(&exec.argv)));
+                out.push_str(&format!("{}    env {}\n", indent, strings(&exec.envp)));
+            } else {
+                self.report_process(*children.next().unwrap(), depth + 1, start, out);
+            }
+        }
+    }
+
+    /// The tree as one JSON object per process, parents before children.
+    pub fn to_json(&self, start: SystemTime) -> Vec<String> {
+        let time = |at: &SystemTime| Timestamps::Relative.format(*at, start);
+        let lossy = |bytes: &Vec<u8>| json::string(&String::from_utf8_lossy(bytes));
+        self.processes.iter().map(|process| {
+            let exited = match &process.ended {
+                Some((_, End::Exited(code))) => Some(*code),
+                _ => None,
+            };
+            let killed = match &process.ended {
+                Some((_, End::Killed(signal, _))) => Some(signal),
+                _ => None,
+            };
+            let execs = json::array(&process.execs, |exec| json::object(&[
+                ("at", time(&exec.at)),
+                ("path", lossy(&exec.path)),
+                ("argv", json::array(&exec.argv, lossy)),
+                ("envp", json::array(&exec.envp, lossy)),
+            ]));
+            json::object(&[
+                ("pid", process.pid.to_string()),
+                ("parent", json::optional(&process.parent,
+                                          |&parent| self.processes[parent].pid.to_string())),
+                ("started", time(&process.started)),
+                ("ended", json::optional(&process.ended, |(at, _)| time(at))),
+                ("exited", json::optional(&exited, |code| code.to_string())),
+                ("killed", json::optional(&killed, |signal| json::string(signal))),
+                ("execs", execs),
+            ])
+        }).collect()
+    }
+}
+
+fn strings(strings: &[Vec<u8>]) -> String {
+    let quoted = strings.iter().map(|s| buffers::escape(s, false)).collect::<Vec<String>>();
+    format!("[{}]", quoted.join(", "))
+}
+
+#[test]
+fn report_test() {
+    use std::time::Duration;
+    let start = SystemTime::now();
+    let at = |ms| start + Duration::from_millis(ms);
+    let mut tree = ProcessTree::new();
+    tree.spawned(Pid::from_raw(10), None, at(0));
+    tree.exec_entered(Pid::from_raw(10), Exec {
+        at: at(1),
+        path: b"/bin/sh".to_vec(),
+        argv: vec![b"sh".to_vec()],
+        envp: vec![b"A=1".to_vec()],
+    });
+    tree.exec_done(Pid::from_raw(10));
+    tree.spawned(Pid::from_raw(11), Some(Pid::from_raw(10)), at(2));
+    tree.killed(Pid::from_raw(11), "SIGKILL".to_string(), false, at(3));
+    tree.exited(Pid::from_raw(10), 1, at(4));
+    assert_eq!(tree.report(start), "10 0.000000..0.004000 exited with 1\n  \
+                                    0.001000 execve(\"/bin/sh\", [\"sh\"])\n    \
+                                    env [\"A=1\"]\n  \
+                                    11 0.002000..0.003000 killed by SIGKILL\n");
+}
@@ -1,72 +1,130 @@
-use byteorder::{LittleEndian, WriteBytesExt};
-
-use nix;
-use libc::{c_void, user_regs_struct, PT_NULL};
+use libc::c_void;
 use nix::sys::ptrace;
 use nix::sys::ptrace::*;
 use nix::unistd::*;
-use std::ptr;
-use std::mem;
+use std::{fs, io, ptr};
 
-/// Given an address in a tracee process specified by pid, read a string at
-/// that address.
-pub fn read_string(address: *mut c_void, pid: Pid) -> String {
-    let mut string = String::new();
-    // Move 8 bytes up each time for next read.
-    let mut count = 0;
-    let word_size = 8;
+/// Our options for a tracee. New threads are always traced, forked children
+/// only if we `follow_forks`. With `seccomp` our filter stops the tracee for
+/// the calls we trace, and the tracee is killed if we exit: without us, the
+/// calls the filter picks fail with `ENOSYS`.
+fn ptrace_options(follow_forks: bool, seccomp: bool) -> Options {
+    let options = Options::PTRACE_O_TRACESYSGOOD
+        | Options::PTRACE_O_TRACECLONE
+        | Options::PTRACE_O_TRACEEXIT
+        | Options::PTRACE_O_TRACEEXEC;
+    let options = if follow_forks {
+        options | Options::PTRACE_O_TRACEFORK | Options::PTRACE_O_TRACEVFORK
+    } else {
+        options
+    };
+    if seccomp {
+        options | Options::PTRACE_O_TRACESECCOMP | Options::PTRACE_O_EXITKILL
+    } else {
+        options
+    }
+}
 
-    'done: loop {
-        let mut bytes: Vec<u8> = vec![];
-        let res = unsafe {
-            #[allow(deprecated)]
-            ptrace::ptrace(Request::PTRACE_PEEKDATA,
-                           pid,
-                           address.offset(count),
-                           ptr::null_mut()).unwrap()
-        };
+/// Start tracing a running task without stopping it, as with
+/// `ptrace(PTRACE_SEIZE, ...)`, setting our options at the same time.
+pub fn ptrace_seize(pid: Pid, follow_forks: bool, seccomp: bool) -> nix::Result<()> {
+    unsafe {
+        #[allow(deprecated)]
+        ptrace::ptrace(Request::PTRACE_SEIZE,
+                       pid,
+                       ptr::null_mut(),
+                       ptrace_options(follow_forks, seccomp).bits() as *mut c_void).map(|_| ())
+    }
+}
 
-        bytes.write_i64::<LittleEndian>(res).unwrap();
-        for b in bytes {
-            if b != 0 {
-                string.push(b as char);
-            }else{
-                break 'done;
-            }
-        }
-        count += word_size;
+/// Stop a seized task, it reports a `PTRACE_EVENT_STOP` once stopped.
+pub fn ptrace_interrupt(pid: Pid) -> nix::Result<()> {
+    unsafe {
+        #[allow(deprecated)]
+        ptrace::ptrace(Request::PTRACE_INTERRUPT,
+                       pid,
+                       ptr::null_mut(),
+                       ptr::null_mut()).map(|_| ())
     }
+}
 
-    string
+/// Stop tracing `pid` and let it run, delivering signal `sig` unless it is
+/// zero.
+pub fn ptrace_detach(pid: Pid, sig: i32) -> nix::Result<()> {
+    unsafe {
+        #[allow(deprecated)]
+        ptrace::ptrace(Request::PTRACE_DETACH,
+                       pid,
+                       ptr::null_mut(),
+                       sig as isize as *mut c_void).map(|_| ())
+    }
 }
 
-pub fn ptrace_set_options(pid: Pid) -> nix::Result<()> {
-    let options = Options::PTRACE_O_TRACESYSGOOD
-        | Options::PTRACE_O_TRACECLONE
-        | Options::PTRACE_O_TRACEFORK
-        | Options::PTRACE_O_TRACEVFORK
-        | Options::PTRACE_O_TRACEEXIT
-        | Options::PTRACE_O_TRACEEXEC;
-    ptrace::setoptions(pid, options)
+/// Resume `pid` until its next system call stop, delivering signal `sig`
+/// unless it is zero.
+pub fn ptrace_syscall(pid: Pid, sig: i32) -> nix::Result<()> {
+    unsafe {
+        #[allow(deprecated)]
+        ptrace::ptrace(Request::PTRACE_SYSCALL,
+                       pid,
+                       ptr::null_mut(),
+                       sig as isize as *mut c_void).map(|_| ())
+    }
 }
 
-/// Nix does not yet have a way to fetch registers. We use our own instead.
-/// Given the pid of a process that is currently being traced. Return the registers
-/// for that process.
-pub fn get_regs(pid: Pid) -> user_regs_struct {
+/// Resume `pid` without stopping at system calls, delivering signal `sig`
+/// unless it is zero.
+pub fn ptrace_cont(pid: Pid, sig: i32) -> nix::Result<()> {
     unsafe {
-        let mut regs: user_regs_struct = mem::uninitialized();
+        #[allow(deprecated)]
+        ptrace::ptrace(Request::PTRACE_CONT,
+                       pid,
+                       ptr::null_mut(),
+                       sig as isize as *mut c_void).map(|_| ())
+    }
+}
 
+/// Let a seized task in a group-stop stay stopped, while still reporting the
+/// `PTRACE_EVENT_STOP` when the stop ends.
+pub fn ptrace_listen(pid: Pid) -> nix::Result<()> {
+    unsafe {
         #[allow(deprecated)]
-        let res = ptrace::ptrace(
-            Request::PTRACE_GETREGS,
-            pid,
-            PT_NULL as *mut c_void,
-            &mut regs as *mut _ as *mut c_void,
-        );
-        match res {
-            Ok(_) => regs,
-            Err(e) => panic!("Get regs failed: {:?}", e),
+        ptrace::ptrace(Request::PTRACE_LISTEN,
+                       pid,
+                       ptr::null_mut(),
+                       ptr::null_mut()).map(|_| ())
+    }
+}
+
+/// The arguments process `pid` was started with, from `/proc/PID/cmdline`.
+pub fn process_cmdline(pid: Pid) -> io::Result<Vec<String>> {
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid))?;
+    // Each argument is NUL terminated.
+    let args = match cmdline.split_last() {
+        Some((0, args)) => args,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(args.split(|&b| b == 0).map(|arg| String::from_utf8_lossy(arg).into_owned()).collect())
+}
+
+/// The thread ids of process `pid`, read from `/proc/PID/task`.
+pub fn process_tasks(pid: Pid) -> io::Result<Vec<Pid>> {
+    let mut tasks = Vec::new();
+    for entry in fs::read_dir(format!("/proc/{}/task", pid))? {
+        if let Ok(tid) = entry?.file_name().to_string_lossy().parse::<i32>() {
+            tasks.push(Pid::from_raw(tid));
         }
     }
+    Ok(tasks)
+}
+
+/// The thread group, that is the process, thread `tid` belongs to. Falls back
+/// to `tid` itself if `/proc` can't tell us.
+pub fn thread_group(tid: Pid) -> Pid {
+    fs::read_to_string(format!("/proc/{}/status", tid)).ok()
+        .and_then(|status| status.lines()
+                  .find(|l| l.starts_with("Tgid:"))
+                  .and_then(|l| l["Tgid:".len()..].trim().parse::<i32>().ok()))
+        .map(Pid::from_raw)
+        .unwrap_or(tid)
 }